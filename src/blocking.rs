@@ -0,0 +1,109 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Blocking client - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Synchronous client, enabled by the `blocking` feature.
+//!
+//! `blocking::OmglolClient` has the same `Auth`/`NoAuth` states and endpoint
+//! methods as [`client::OmglolClient`], without `async`. Each client drives
+//! its requests on a small runtime of its own, shared by its clones, so it
+//! must not be used from inside an async runtime.
+//!
+//! Usage:
+//! ```rust,no_run
+//! use omglol::blocking::OmglolClient;
+//!
+//! let client = OmglolClient::new().unwrap();
+//! let status = client.service_status().unwrap();
+//! println!("{}", status.response.message);
+//! ```
+
+use std::{future::Future, sync::Arc};
+
+use tokio::runtime::{Builder, Runtime};
+
+use crate::client::{self, Auth, NoAuth};
+use crate::credentials::CredentialProvider;
+use crate::error::OmglolError;
+use crate::structures::Token;
+
+/// Blocking client for api.omg.lol
+#[derive(Clone)]
+pub struct OmglolClient<State = NoAuth> {
+    pub(crate) inner: client::OmglolClient<State>,
+    runtime: Arc<Runtime>,
+}
+
+impl OmglolClient {
+    /// Create a new unauthenticated `OmglolClient`.
+    ///
+    /// Usage:
+    /// ```rust
+    /// use omglol::blocking::OmglolClient;
+    ///
+    /// let client = OmglolClient::new().unwrap();
+    /// ```
+    pub fn new() -> Result<OmglolClient<NoAuth>, OmglolError> {
+        OmglolClient::from_async(client::OmglolClient::new())
+    }
+}
+
+impl<State> OmglolClient<State> {
+    /// Wrap an async client, e.g. one configured with
+    /// [`client::OmglolClient::builder`].
+    ///
+    /// Fails if the runtime that drives the requests cannot be started.
+    pub fn from_async(
+        client: client::OmglolClient<State>,
+    ) -> Result<OmglolClient<State>, OmglolError> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(OmglolClient {
+            inner: client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The async client requests are made with.
+    pub fn as_async(&self) -> &client::OmglolClient<State> {
+        &self.inner
+    }
+
+    pub(crate) fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    fn with_inner<Other>(&self, inner: client::OmglolClient<Other>) -> OmglolClient<Other> {
+        OmglolClient {
+            inner,
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl OmglolClient<NoAuth> {
+    /// Create an authenticated `OmglolClient`, see
+    /// [`client::OmglolClient::auth`].
+    pub fn auth(&self, api_key: String) -> OmglolClient<Auth> {
+        self.with_inner(self.inner.auth(api_key))
+    }
+
+    /// Create an `OmglolClient` authenticated with an OAuth access token.
+    pub fn auth_with_token(&self, token: &Token) -> OmglolClient<Auth> {
+        self.with_inner(self.inner.auth_with_token(token))
+    }
+
+    /// Create an authenticated `OmglolClient` asking `credentials` for the
+    /// bearer token before every request.
+    pub fn auth_with(&self, credentials: impl CredentialProvider + 'static) -> OmglolClient<Auth> {
+        self.with_inner(self.inner.auth_with(credentials))
+    }
+}
@@ -11,375 +11,706 @@ This Source Code Form is subject to the terms of the Mozilla Public License, v.
  You can obtain one at https://mozilla.org/MPL/2.0/.
 */
 
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::email::format_addresses_string;
+use crate::credentials::{CredentialProvider, StaticKey};
+use crate::email::{validate_destinations, ForwardingUpdate};
+use crate::error::OmglolError;
+use crate::rate_limit::RateLimiter;
+use crate::retry::{self, RetryPolicy};
 use crate::structures::*;
+#[cfg(feature = "tracing")]
+use crate::telemetry;
+use crate::transport::{HttpRequest, ReqwestTransport, Transport};
 use email_address::EmailAddress;
-use reqwest::{Client, Method};
+use futures_timer::Delay;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, AUTHORIZATION},
+    Client, Method,
+};
 use serde::de::DeserializeOwned;
 use serde_json;
+use url::Url;
+
+/// Base URL of the public omg.lol API, used unless another one is configured
+/// through [`OmglolClientBuilder::base_url`].
+pub const DEFAULT_BASE_URL: &str = "https://api.omg.lol/";
 
 macro_rules! api_endpoint (
-    ($path: expr) => (
-        format!("{}{}", "https://api.omg.lol/", $path)
+    ($base_url: expr, $path: expr) => (
+        format!("{}{}", $base_url, $path)
     );
 );
 
-pub(crate) use api_endpoint;
+/// Build a `Route` from a path template and its placeholders, each given as
+/// a variable in scope (`address`) or a named argument (`address = expr`).
+///
+/// Every placeholder must be listed: values are percent-encoded in the
+/// request URL, so that e.g. a `?` in a paste title stays in its segment.
+macro_rules! route (
+    ($template: literal $(, $name: ident $(= $value: expr)?)* $(,)?) => ({
+        $(let $name = &route!(@value $name $(, $value)?);)*
+        Route::new($template, format!($template $(, $name = $name)*))
+            .encoded_path(format!($template $(, $name = encode_segment(&$name.to_string()))*))
+    });
+    (@value $name: ident) => ($name);
+    (@value $name: ident, $value: expr) => ($value);
+);
+
+/// Define the endpoint methods of `OmglolClient<State>` and, with the
+/// `blocking` feature, the matching methods of
+/// `blocking::OmglolClient<State>`, so both clients share one definition.
+macro_rules! endpoints (
+    (
+        impl OmglolClient<$state: ident> {
+            $(
+                $(#[$meta: meta])*
+                pub async fn $name: ident $(<$($generic: ident: $bound: path),+>)? (
+                    &$self: ident $(, $arg: ident: $arg_ty: ty)* $(,)?
+                ) -> $ret: ty $body: block
+            )*
+        }
+    ) => (
+        impl OmglolClient<$state> {
+            $(
+                $(#[$meta])*
+                pub async fn $name $(<$($generic: $bound),+>)? (
+                    &$self $(, $arg: $arg_ty)*
+                ) -> $ret $body
+            )*
+        }
+
+        #[cfg(feature = "blocking")]
+        impl crate::blocking::OmglolClient<$state> {
+            $(
+                $(#[$meta])*
+                pub fn $name $(<$($generic: $bound),+>)? (
+                    &$self $(, $arg: $arg_ty)*
+                ) -> $ret {
+                    $self.block_on($self.inner.$name($($arg),*))
+                }
+            )*
+        }
+    );
+);
 
+#[derive(Debug, Clone, Copy)]
 pub struct Auth;
+#[derive(Debug, Clone, Copy)]
 pub struct NoAuth;
 
 /// Client for api.omg.lol
 #[derive(Clone)]
 pub struct OmglolClient<State = NoAuth> {
-    client: Client,
-    api_key: Option<String>,
+    transport: Arc<dyn Transport>,
+    base_url: String,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
     state: PhantomData<State>,
 }
 
-impl OmglolClient<Auth> {
-    pub async fn get_dns_records(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<DNSrecords>, Box<dyn std::error::Error>> {
-        self.send_request::<DNSrecords>(
-            true,
-            Method::GET,
-            format!("address/{}/dns", &address).as_ref(),
-            None,
-        )
-        .await
-    }
+/// Builder for an unauthenticated `OmglolClient`.
+///
+/// Usage:
+/// ```rust
+/// use omglol::OmglolClient;
+///
+/// let client = OmglolClient::builder()
+///     .base_url("http://localhost:8080")
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct OmglolClientBuilder {
+    transport: Option<Arc<dyn Transport>>,
+    base_url: String,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+}
 
-    pub async fn delete_dns_record(
-        &self,
-        address: &str,
-        id: &str,
-    ) -> Result<RequestResponse<DNSrecords>, Box<dyn std::error::Error>> {
-        self.send_request::<DNSrecords>(
-            true,
-            Method::DELETE,
-            format!("address/{address}/dns/{id}").as_ref(),
-            None,
-        )
-        .await
+impl OmglolClientBuilder {
+    /// Set the base URL every endpoint path is appended to.
+    ///
+    /// Defaults to [`DEFAULT_BASE_URL`]. A trailing slash is added if missing,
+    /// so both `http://localhost:8080` and `http://localhost:8080/` work.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> OmglolClientBuilder {
+        let mut base_url = base_url.into();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        self.base_url = base_url;
+        self
     }
 
-    pub async fn get_status(
-        &self,
-        address: &str,
-        id: &str,
-    ) -> Result<RequestResponse<StatuslogResponseArray>, Box<dyn std::error::Error>> {
-        self.send_request::<StatuslogResponseArray>(
-            true,
-            Method::GET,
-            format!("address/{}/statuses/{}", &address, &id).as_ref(),
-            None,
-        )
-        .await
+    /// Use an existing `reqwest::Client` instead of creating a new one.
+    pub fn http_client(self, client: Client) -> OmglolClientBuilder {
+        self.transport(ReqwestTransport::new(client))
     }
 
-    pub async fn post_status(
-        &self,
-        status: &Status,
-    ) -> Result<RequestResponse<StatuslogResponseArray>, Box<dyn std::error::Error>> {
-        self.send_request::<StatuslogResponseArray>(
-            true,
-            Method::POST,
-            format!("address/{}/statuses", &status.address).as_ref(),
-            Some(serde_json::to_string(&status)?),
-        )
-        .await
+    /// Send requests through `transport` instead of reqwest, e.g. a
+    /// [`MemoryTransport`](crate::transport::MemoryTransport) in tests.
+    pub fn transport(mut self, transport: impl Transport + 'static) -> OmglolClientBuilder {
+        self.transport = Some(Arc::new(transport));
+        self
     }
 
-    pub async fn update_status(
-        &self,
-        status: &Status,
-    ) -> Result<RequestResponse<StatuslogUpdateResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<StatuslogUpdateResponse>(
-            true,
-            Method::POST,
-            format!("address/{}/status", &status.address).as_ref(),
-            Some(serde_json::to_string(&status)?),
-        )
-        .await
+    /// Retry failed requests according to `retry_policy`.
+    ///
+    /// Defaults to [`RetryPolicy::none`], i.e. a single attempt.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> OmglolClientBuilder {
+        self.retry_policy = retry_policy;
+        self
     }
 
-    pub async fn update_statuslog_bio<T: ContentAsJSON>(
-        &self,
-        bio: T,
-        address: &str,
-    ) -> Result<RequestResponse<StatuslogBio>, Box<dyn std::error::Error>> {
-        self.send_request::<StatuslogBio>(
-            true,
-            Method::POST,
-            format!("address/{}/statuses/bio", &address).as_ref(),
-            Some(bio.json_content()),
-        )
-        .await
+    /// Throttle requests with `rate_limiter`, shared by every clone of the
+    /// client and every client it authenticates.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> OmglolClientBuilder {
+        self.rate_limiter = Some(rate_limiter);
+        self
     }
 
-    // Email
-
-    pub async fn get_forwarding_addresses(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<ForwardingAddresses>, Box<dyn std::error::Error>> {
-        self.send_request::<ForwardingAddresses>(
-            true,
-            Method::GET,
-            format!("address/{}/email", &address).as_ref(),
-            None,
-        )
-        .await
+    /// Build the unauthenticated `OmglolClient`.
+    pub fn build(self) -> OmglolClient<NoAuth> {
+        OmglolClient {
+            transport: self
+                .transport
+                .unwrap_or_else(|| Arc::new(ReqwestTransport::default())),
+            base_url: self.base_url,
+            credentials: None,
+            retry_policy: self.retry_policy,
+            rate_limiter: self.rate_limiter,
+            state: PhantomData,
+        }
     }
+}
 
-    pub async fn set_forwarding_addresses(
-        &self,
-        address: &str,
-        destination: &Vec<EmailAddress>,
-    ) -> Result<RequestResponse<ForwardingAddresses>, Box<dyn std::error::Error>> {
-        self.send_request::<ForwardingAddresses>(
-            true,
-            Method::GET,
-            format!("address/{}/email", &address).as_ref(),
-            Some(format_addresses_string(destination)),
-        )
-        .await
+impl Default for OmglolClientBuilder {
+    fn default() -> OmglolClientBuilder {
+        OmglolClientBuilder {
+            transport: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            retry_policy: RetryPolicy::none(),
+            rate_limiter: None,
+        }
     }
+}
 
-    // Pastebin
+endpoints! {
+    impl OmglolClient<Auth> {
+        pub async fn get_dns_records(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<DNSrecords>, OmglolError> {
+            self.send_request::<DNSrecords>(
+                true,
+                Method::GET,
+                route!("address/{address}/dns", address),
+                None,
+            )
+            .await
+        }
 
-    pub async fn get_pastebin(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<PastebinResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<PastebinResponse>(
-            true,
-            Method::GET,
-            format!("address/{}/pastebin", &address).as_ref(),
-            None,
-        )
-        .await
-    }
+        /// Create a DNS record for `address`.
+        ///
+        /// The record is validated before it is sent.
+        pub async fn create_dns_record(
+            &self,
+            address: &str,
+            record: &DNSrecordBuilder,
+        ) -> Result<RequestResponse<DNSrecordResponse>, OmglolError> {
+            record.validate()?;
+            self.send_request::<DNSrecordResponse>(
+                true,
+                Method::POST,
+                route!("address/{address}/dns", address),
+                Some(serde_json::to_string(&record)?),
+            )
+            .await
+        }
 
-    pub async fn create_weblog_entry(
-        &self,
-        content: &str,
-        entry_id: &str,
-        address: &str,
-    ) -> Result<RequestResponse<WeblogEntryResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<WeblogEntryResponse>(
-            true,
-            Method::POST,
-            format!("address/{}/weblog/entry/{}", address, entry_id).as_ref(),
-            Some(content.to_string()),
-        )
-        .await
-    }
+        /// Replace the DNS record `id` of `address`.
+        ///
+        /// The record is validated before it is sent.
+        pub async fn update_dns_record(
+            &self,
+            address: &str,
+            id: i32,
+            record: &DNSrecordBuilder,
+        ) -> Result<RequestResponse<DNSrecordResponse>, OmglolError> {
+            record.validate()?;
+            self.send_request::<DNSrecordResponse>(
+                true,
+                Method::PATCH,
+                route!("address/{address}/dns/{id}", address, id),
+                Some(serde_json::to_string(&record)?),
+            )
+            .await
+        }
 
-    pub async fn update_weblog_configuration(
-        &self,
-        configuration: &str,
-        address: &str,
-    ) -> Result<RequestResponse<WeblogEntryResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<WeblogEntryResponse>(
-            true,
-            Method::POST,
-            format!("address/{}/weblog/template", address).as_ref(),
-            Some(configuration.to_string()),
-        )
-        .await
-    }
-    pub async fn get_purl(
-        &self,
-        address: &str,
-        purl_address: &str,
-    ) -> Result<RequestResponse<PurlResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<PurlResponse>(
-            true,
-            Method::GET,
-            format!("address/{}/purl/{purl_address}", &address).as_ref(),
-            None,
-        )
-        .await
-    }
+        /// Delete the DNS record `id` of `address`.
+        pub async fn delete_dns_record(
+            &self,
+            address: &str,
+            id: i32,
+        ) -> Result<RequestResponse<MessageResponse>, OmglolError> {
+            self.send_request::<MessageResponse>(
+                true,
+                Method::DELETE,
+                route!("address/{address}/dns/{id}", address, id),
+                None,
+            )
+            .await
+        }
 
-    pub async fn get_all_purls(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<PurlsResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<PurlsResponse>(
-            true,
-            Method::GET,
-            format!("address/{}/purls", &address).as_ref(),
-            None,
-        )
-        .await
-    }
+        pub async fn get_status(
+            &self,
+            address: &str,
+            id: &str,
+        ) -> Result<RequestResponse<StatuslogStatus>, OmglolError> {
+            self.send_request::<StatuslogStatus>(
+                true,
+                Method::GET,
+                route!("address/{address}/statuses/{id}", address, id),
+                None,
+            )
+            .await
+        }
 
-    pub async fn delete_purl(
-        &self,
-        address: &str,
-        purl_address: &str,
-    ) -> Result<RequestResponse<MessageResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<MessageResponse>(
-            true,
-            Method::DELETE,
-            format!("address/{}/purl/{purl_address}", &address).as_ref(),
-            None,
-        )
-        .await
-    }
+        pub async fn post_status(
+            &self,
+            status: &Status,
+        ) -> Result<RequestResponse<StatusPostResponse>, OmglolError> {
+            self.send_request::<StatusPostResponse>(
+                true,
+                Method::POST,
+                route!("address/{address}/statuses", address = status.address),
+                Some(serde_json::to_string(&status)?),
+            )
+            .await
+        }
 
-    pub async fn get_account_info(
-        &self,
-        email: &EmailAddress,
-    ) -> Result<RequestResponse<AccountResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<AccountResponse>(
-            true,
-            Method::GET,
-            format!("account/{}/info", email).as_ref(),
-            None,
-        )
-        .await
-    }
+        /// Replace the content of the status `status.id`.
+        pub async fn update_status(
+            &self,
+            status: &Status,
+        ) -> Result<RequestResponse<StatuslogUpdateResponse>, OmglolError> {
+            self.send_request::<StatuslogUpdateResponse>(
+                true,
+                Method::PATCH,
+                route!("address/{address}/statuses", address = status.address),
+                Some(serde_json::to_string(&status)?),
+            )
+            .await
+        }
 
-    pub async fn get_private_address_info(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<Address>, Box<dyn std::error::Error>> {
-        self.send_request::<Address>(
-            true,
-            Method::GET,
-            format!("account/{}/info", address).as_ref(),
-            None,
-        )
-        .await
-    }
+        /// Delete the status `id` of `address`.
+        pub async fn delete_status(
+            &self,
+            address: &str,
+            id: &str,
+        ) -> Result<RequestResponse<MessageResponse>, OmglolError> {
+            self.send_request::<MessageResponse>(
+                true,
+                Method::DELETE,
+                route!("address/{address}/statuses/{id}", address, id),
+                None,
+            )
+            .await
+        }
 
-    pub async fn get_address_expiration(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<Expiration>, Box<dyn std::error::Error>> {
-        self.send_request::<Expiration>(
-            true,
-            Method::GET,
-            format!("account/{}/expiration", address).as_ref(),
-            None,
-        )
-        .await
-    }
+        pub async fn update_statuslog_bio<T: ContentAsJSON>(
+            &self,
+            bio: T,
+            address: &str,
+        ) -> Result<RequestResponse<StatuslogBio>, OmglolError> {
+            self.send_request::<StatuslogBio>(
+                true,
+                Method::POST,
+                route!("address/{address}/statuses/bio", address),
+                Some(bio.json_content()),
+            )
+            .await
+        }
 
-    pub async fn get_web_page(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<Web>, Box<dyn std::error::Error>> {
-        self.send_request::<Web>(
-            true,
-            Method::GET,
-            format!("address/{}/web", address).as_ref(),
-            None,
-        )
-        .await
-    }
+        // Email
 
-    pub async fn update_web_page(
-        &self,
-        web: &Web,
-        address: &str,
-    ) -> Result<RequestResponse<MessageResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<MessageResponse>(
-            true,
-            Method::POST,
-            format!("address/{}/web", address).as_ref(),
-            Some(serde_json::to_string(&web)?),
-        )
-        .await
-    }
+        pub async fn get_forwarding_addresses(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<ForwardingAddresses>, OmglolError> {
+            self.send_request::<ForwardingAddresses>(
+                true,
+                Method::GET,
+                route!("address/{address}/email", address),
+                None,
+            )
+            .await
+        }
 
-    pub async fn get_weblog_entry(
-        &self,
-        entry_id: &str,
-        address: &str,
-    ) -> Result<RequestResponse<WeblogEntryResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<WeblogEntryResponse>(
-            true,
-            Method::GET,
-            format!("address/{}/weblog/entry/{}", address, entry_id).as_ref(),
-            None,
-        )
-        .await
-    }
+        /// Forward mail for `address@omg.lol` to `destinations`.
+        ///
+        /// There must be at least one destination, and `address@omg.lol` may
+        /// not forward to itself. Use `clear_forwarding_addresses` to turn
+        /// forwarding off.
+        pub async fn set_forwarding_addresses(
+            &self,
+            address: &str,
+            destinations: &[EmailAddress],
+        ) -> Result<RequestResponse<ForwardingAddresses>, OmglolError> {
+            validate_destinations(address, destinations)?;
+            self.send_request::<ForwardingAddresses>(
+                true,
+                Method::POST,
+                route!("address/{address}/email", address),
+                Some(serde_json::to_string(&ForwardingUpdate::new(
+                    destinations,
+                ))?),
+            )
+            .await
+        }
 
-    pub async fn delete_weblog_entry(
-        &self,
-        entry_id: &str,
-        address: &str,
-    ) -> Result<RequestResponse<MessageResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<MessageResponse>(
-            true,
-            Method::DELETE,
-            format!("address/{}/weblog/delete/{}", address, entry_id).as_ref(),
-            None,
-        )
-        .await
-    }
+        /// Stop forwarding mail for `address@omg.lol`.
+        pub async fn clear_forwarding_addresses(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<ForwardingAddresses>, OmglolError> {
+            self.send_request::<ForwardingAddresses>(
+                true,
+                Method::POST,
+                route!("address/{address}/email", address),
+                Some(serde_json::to_string(&ForwardingUpdate::new(&[]))?),
+            )
+            .await
+        }
 
-    pub async fn get_weblog_configuration(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<WeblogConfigurationResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<WeblogConfigurationResponse>(
-            true,
-            Method::GET,
-            format!("address/{}/weblog/configuration", address).as_ref(),
-            None,
-        )
-        .await
-    }
+        // Now page
 
-    pub async fn get_weblog_template(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<WeblogTemplateResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<WeblogTemplateResponse>(
-            true,
-            Method::GET,
-            format!("address/{}/weblog/template", address).as_ref(),
-            None,
-        )
-        .await
-    }
+        /// Get the Now page of `address`.
+        pub async fn get_now_page(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<NowResponse>, OmglolError> {
+            self.send_request::<NowResponse>(
+                true,
+                Method::GET,
+                route!("address/{address}/now", address),
+                None,
+            )
+            .await
+        }
 
-    pub async fn update_weblog_template(
-        &self,
-        template: &str,
-        address: &str,
-    ) -> Result<RequestResponse<WeblogTemplateResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<WeblogTemplateResponse>(
-            true,
-            Method::POST,
-            format!("address/{}/weblog/template", address).as_ref(),
-            Some(template.to_string()),
-        )
-        .await
-    }
+        /// Replace the content of the Now page of `address`.
+        pub async fn update_now_page(
+            &self,
+            address: &str,
+            now: &NowPageUpdate,
+        ) -> Result<RequestResponse<MessageResponse>, OmglolError> {
+            self.send_request::<MessageResponse>(
+                true,
+                Method::POST,
+                route!("address/{address}/now", address),
+                Some(serde_json::to_string(&now)?),
+            )
+            .await
+        }
 
-    pub async fn delete_paste(
-        &self,
-        address: &str,
-        title: &str,
-    ) -> Result<RequestResponse<MessageResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<MessageResponse>(
-            false,
-            Method::DELETE,
-            format!("address/{}/pastebin/{title}", &address).as_ref(),
-            None,
-        )
-        .await
+        // Pastebin
+
+        /// Get every paste of `address`, listed and unlisted.
+        pub async fn get_pastebin(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<PastebinResponse>, OmglolError> {
+            self.send_request::<PastebinResponse>(
+                true,
+                Method::GET,
+                route!("address/{address}/pastebin", address),
+                None,
+            )
+            .await
+        }
+
+        /// Create a paste, or replace the paste with the same title.
+        pub async fn upload_paste(
+            &self,
+            address: &str,
+            paste: &Paste,
+        ) -> Result<RequestResponse<PasteUploadResponse>, OmglolError> {
+            if paste.title.trim().is_empty() {
+                return Err(OmglolError::Validation("paste title is empty".to_string()));
+            }
+            self.send_request::<PasteUploadResponse>(
+                true,
+                Method::POST,
+                route!("address/{address}/pastebin", address),
+                Some(serde_json::to_string(paste)?),
+            )
+            .await
+        }
+
+        /// Delete the paste `title` of `address`.
+        pub async fn delete_paste(
+            &self,
+            address: &str,
+            title: &str,
+        ) -> Result<RequestResponse<MessageResponse>, OmglolError> {
+            self.send_request::<MessageResponse>(
+                true,
+                Method::DELETE,
+                route!("address/{address}/pastebin/{title}", address, title),
+                None,
+            )
+            .await
+        }
+
+        pub async fn create_weblog_entry(
+            &self,
+            content: &str,
+            entry_id: &str,
+            address: &str,
+        ) -> Result<RequestResponse<WeblogEntryResponse>, OmglolError> {
+            self.send_request::<WeblogEntryResponse>(
+                true,
+                Method::POST,
+                route!("address/{address}/weblog/entry/{entry_id}", address, entry_id),
+                Some(content.to_string()),
+            )
+            .await
+        }
+
+        pub async fn update_weblog_configuration(
+            &self,
+            configuration: &str,
+            address: &str,
+        ) -> Result<RequestResponse<MessageResponse>, OmglolError> {
+            self.send_request::<MessageResponse>(
+                true,
+                Method::POST,
+                route!("address/{address}/weblog/configuration", address),
+                Some(configuration.to_string()),
+            )
+            .await
+        }
+
+        /// Create the PURL `name` of `address`, redirecting to `url`.
+        ///
+        /// `url` must be an absolute `http` or `https` URL. The API only
+        /// answers with a message, so the returned `Purl` is built from the
+        /// arguments rather than decoded, and its `counter` is `None`.
+        pub async fn create_purl(
+            &self,
+            address: &str,
+            name: &str,
+            url: &str,
+            listed: bool,
+        ) -> Result<RequestResponse<PurlResponse>, OmglolError> {
+            if name.trim().is_empty() {
+                return Err(OmglolError::Validation("PURL name is empty".to_string()));
+            }
+            let url = Url::parse(url)
+                .ok()
+                .filter(|url| matches!(url.scheme(), "http" | "https"))
+                .ok_or_else(|| {
+                    OmglolError::Validation(format!("{:?} is not an http(s) URL", url))
+                })?;
+
+            let purl = PurlUpdate::new(name.to_string(), url, listed);
+            let response = self
+                .send_request::<MessageResponse>(
+                    true,
+                    Method::POST,
+                    route!("address/{address}/purl", address),
+                    Some(serde_json::to_string(&purl)?),
+                )
+                .await?;
+
+            Ok(response.map(|response| PurlResponse {
+                message: response.message,
+                purl: Purl {
+                    name: purl.name,
+                    url: purl.url,
+                    counter: None,
+                    listed: purl.listed,
+                },
+            }))
+        }
+
+        pub async fn get_purl(
+            &self,
+            address: &str,
+            purl_address: &str,
+        ) -> Result<RequestResponse<PurlResponse>, OmglolError> {
+            self.send_request::<PurlResponse>(
+                true,
+                Method::GET,
+                route!("address/{address}/purl/{purl_address}", address, purl_address),
+                None,
+            )
+            .await
+        }
+
+        pub async fn get_all_purls(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<PurlsResponse>, OmglolError> {
+            self.send_request::<PurlsResponse>(
+                true,
+                Method::GET,
+                route!("address/{address}/purls", address),
+                None,
+            )
+            .await
+        }
+
+        pub async fn delete_purl(
+            &self,
+            address: &str,
+            purl_address: &str,
+        ) -> Result<RequestResponse<MessageResponse>, OmglolError> {
+            self.send_request::<MessageResponse>(
+                true,
+                Method::DELETE,
+                route!("address/{address}/purl/{purl_address}", address, purl_address),
+                None,
+            )
+            .await
+        }
+
+        pub async fn get_account_info(
+            &self,
+            email: &EmailAddress,
+        ) -> Result<RequestResponse<AccountResponse>, OmglolError> {
+            self.send_request::<AccountResponse>(
+                true,
+                Method::GET,
+                route!("account/{email}/info", email),
+                None,
+            )
+            .await
+        }
+
+        pub async fn get_private_address_info(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<Address>, OmglolError> {
+            self.send_request::<Address>(
+                true,
+                Method::GET,
+                route!("address/{address}/info", address),
+                None,
+            )
+            .await
+        }
+
+        pub async fn get_address_expiration(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<Expiration>, OmglolError> {
+            self.send_request::<Expiration>(
+                true,
+                Method::GET,
+                route!("address/{address}/expiration", address),
+                None,
+            )
+            .await
+        }
+
+        pub async fn get_web_page(&self, address: &str) -> Result<RequestResponse<Web>, OmglolError> {
+            self.send_request::<Web>(
+                true,
+                Method::GET,
+                route!("address/{address}/web", address),
+                None,
+            )
+            .await
+        }
+
+        pub async fn update_web_page(
+            &self,
+            web: &Web,
+            address: &str,
+        ) -> Result<RequestResponse<MessageResponse>, OmglolError> {
+            self.send_request::<MessageResponse>(
+                true,
+                Method::POST,
+                route!("address/{address}/web", address),
+                Some(serde_json::to_string(&web)?),
+            )
+            .await
+        }
+
+        pub async fn get_weblog_entry(
+            &self,
+            entry_id: &str,
+            address: &str,
+        ) -> Result<RequestResponse<WeblogEntryResponse>, OmglolError> {
+            self.send_request::<WeblogEntryResponse>(
+                true,
+                Method::GET,
+                route!("address/{address}/weblog/entry/{entry_id}", address, entry_id),
+                None,
+            )
+            .await
+        }
+
+        pub async fn delete_weblog_entry(
+            &self,
+            entry_id: &str,
+            address: &str,
+        ) -> Result<RequestResponse<MessageResponse>, OmglolError> {
+            self.send_request::<MessageResponse>(
+                true,
+                Method::DELETE,
+                route!("address/{address}/weblog/delete/{entry_id}", address, entry_id),
+                None,
+            )
+            .await
+        }
+
+        pub async fn get_weblog_configuration(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<WeblogConfigurationResponse>, OmglolError> {
+            self.send_request::<WeblogConfigurationResponse>(
+                true,
+                Method::GET,
+                route!("address/{address}/weblog/configuration", address),
+                None,
+            )
+            .await
+        }
+
+        pub async fn get_weblog_template(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<WeblogTemplateResponse>, OmglolError> {
+            self.send_request::<WeblogTemplateResponse>(
+                true,
+                Method::GET,
+                route!("address/{address}/weblog/template", address),
+                None,
+            )
+            .await
+        }
+
+        pub async fn update_weblog_template(
+            &self,
+            template: &str,
+            address: &str,
+        ) -> Result<RequestResponse<WeblogTemplateResponse>, OmglolError> {
+            self.send_request::<WeblogTemplateResponse>(
+                true,
+                Method::POST,
+                route!("address/{address}/weblog/template", address),
+                Some(template.to_string()),
+            )
+            .await
+        }
     }
 }
 
@@ -390,13 +721,40 @@ impl OmglolClient<NoAuth> {
     ///
     /// Example:
     /// ```rust
+    /// use omglol::OmglolClient;
+    ///
     /// let client = OmglolClient::new();
     /// let client = client.auth("YOUR_API_KEY".to_string());
     /// ```
     pub fn auth(&self, api_key: String) -> OmglolClient<Auth> {
+        self.auth_with(StaticKey::new(api_key))
+    }
+
+    /// Create an authenticated `OmglolClient` acting on behalf of the user
+    /// who granted `token`, see [`crate::oauth`].
+    ///
+    /// Use [`OAuthToken`](crate::credentials::OAuthToken) with
+    /// [`auth_with`](Self::auth_with) if the token needs to be replaced later.
+    pub fn auth_with_token(&self, token: &Token) -> OmglolClient<Auth> {
+        self.auth(token.access_token.clone())
+    }
+
+    /// Create an authenticated `OmglolClient` asking `credentials` for the
+    /// bearer token before every request.
+    ///
+    /// Example:
+    /// ```rust
+    /// use omglol::{credentials::ConfigFile, OmglolClient};
+    ///
+    /// let client = OmglolClient::new().auth_with(ConfigFile::new("omglol.toml"));
+    /// ```
+    pub fn auth_with(&self, credentials: impl CredentialProvider + 'static) -> OmglolClient<Auth> {
         OmglolClient {
-            client: self.client.to_owned(),
-            api_key: Some(api_key),
+            transport: self.transport.to_owned(),
+            base_url: self.base_url.to_owned(),
+            credentials: Some(Arc::new(credentials)),
+            retry_policy: self.retry_policy.to_owned(),
+            rate_limiter: self.rate_limiter.to_owned(),
             state: PhantomData,
         }
     }
@@ -410,121 +768,157 @@ impl OmglolClient {
     ///
     /// Usage:
     /// ```rust
+    /// use omglol::OmglolClient;
+    ///
     /// let client = OmglolClient::new();
     /// ```
     pub fn new() -> OmglolClient<NoAuth> {
-        OmglolClient {
-            client: Client::new(),
-            api_key: None,
-            state: PhantomData,
-        }
+        OmglolClient::builder().build()
     }
 
-    pub async fn get_profile_themes(
-        &self,
-    ) -> Result<RequestResponse<ProfileThemes>, Box<dyn std::error::Error>> {
-        self.send_request::<ProfileThemes>(false, Method::GET, "theme/list", None)
-            .await
+    /// Create an `OmglolClientBuilder` to configure a new `OmglolClient`,
+    /// e.g. to point it at a mirror or a local mock server.
+    pub fn builder() -> OmglolClientBuilder {
+        OmglolClientBuilder::default()
     }
+}
 
-    pub async fn service_status(
-        &self,
-    ) -> Result<RequestResponse<ServiceStatus>, Box<dyn std::error::Error>> {
-        self.send_request::<ServiceStatus>(false, Method::GET, "service/info", None)
+endpoints! {
+    impl OmglolClient<NoAuth> {
+        pub async fn get_profile_themes(&self) -> Result<RequestResponse<ProfileThemes>, OmglolError> {
+            self.send_request::<ProfileThemes>(false, Method::GET, route!("theme/list"), None)
+                .await
+        }
+
+        pub async fn service_status(&self) -> Result<RequestResponse<ServiceStatus>, OmglolError> {
+            self.send_request::<ServiceStatus>(false, Method::GET, route!("service/info"), None)
+                .await
+        }
+
+        pub async fn get_statuslog_bio(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<StatuslogBio>, OmglolError> {
+            self.send_request::<StatuslogBio>(
+                false,
+                Method::GET,
+                route!("address/{address}/statuses/bio", address),
+                None,
+            )
             .await
-    }
+        }
 
-    pub async fn get_statuslog_bio(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<StatuslogBio>, Box<dyn std::error::Error>> {
-        self.send_request::<StatuslogBio>(
-            false,
-            Method::GET,
-            format!("address/{}/statuses/bio", &address).as_ref(),
-            None,
-        )
-        .await
-    }
+        /// Get the listed pastes of `address`.
+        pub async fn get_listed_pastes(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<PastebinResponse>, OmglolError> {
+            self.send_request::<PastebinResponse>(
+                false,
+                Method::GET,
+                route!("address/{address}/pastebin", address),
+                None,
+            )
+            .await
+        }
 
-    pub async fn get_listed_pastes(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<PastebinResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<PastebinResponse>(
-            false,
-            Method::GET,
-            format!("address/{}/pastebin", &address).as_ref(),
-            None,
-        )
-        .await
-    }
+        pub async fn get_paste(
+            &self,
+            address: &str,
+            title: &str,
+        ) -> Result<RequestResponse<PasteResponse>, OmglolError> {
+            self.send_request::<PasteResponse>(
+                false,
+                Method::GET,
+                route!("address/{address}/pastebin/{title}", address, title),
+                None,
+            )
+            .await
+        }
 
-    pub async fn get_paste(
-        &self,
-        address: &str,
-        title: &str,
-    ) -> Result<RequestResponse<PasteResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<PasteResponse>(
-            false,
-            Method::GET,
-            format!("address/{}/pastebin/{title}", &address).as_ref(),
-            None,
-        )
-        .await
-    }
+        pub async fn get_public_address_info(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<Address>, OmglolError> {
+            self.send_request::<Address>(
+                false,
+                Method::GET,
+                route!("address/{address}/info", address),
+                None,
+            )
+            .await
+        }
 
-    pub async fn upload_paste(
-        &self,
-        address: &str,
-        paste: Paste,
-    ) -> Result<RequestResponse<PasteResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<PasteResponse>(
-            false,
-            Method::POST,
-            format!("address/{}/pastebin", &address).as_ref(),
-            Some(serde_json::to_string(&paste)?),
-        )
-        .await
-    }
+        pub async fn get_latest_weblog_post(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<WeblogEntryResponse>, OmglolError> {
+            self.send_request::<WeblogEntryResponse>(
+                false,
+                Method::GET,
+                route!("address/{address}/weblog/post/latest", address),
+                None,
+            )
+            .await
+        }
 
-    pub async fn get_public_address_info(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<Address>, Box<dyn std::error::Error>> {
-        self.send_request::<Address>(
-            false,
-            Method::GET,
-            format!("account/{}/info", address).as_ref(),
-            None,
-        )
-        .await
-    }
+        pub async fn get_all_statuses(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<StatuslogAllStatuses>, OmglolError> {
+            self.send_request::<StatuslogAllStatuses>(
+                false,
+                Method::GET,
+                route!("address/{address}/statuses", address),
+                None,
+            )
+            .await
+        }
 
-    pub async fn get_latest_weblog_post(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<WeblogEntryResponse>, Box<dyn std::error::Error>> {
-        self.send_request::<WeblogEntryResponse>(
-            false,
-            Method::GET,
-            format!("address/{}/weblog/post/latest", address).as_ref(),
-            None,
-        )
-        .await
+        /// Get the most recent status of `address`.
+        pub async fn get_latest_status(
+            &self,
+            address: &str,
+        ) -> Result<RequestResponse<StatuslogStatus>, OmglolError> {
+            self.send_request::<StatuslogStatus>(
+                false,
+                Method::GET,
+                route!("address/{address}/statuses/latest", address),
+                None,
+            )
+            .await
+        }
+
+        /// Get the Now Garden, the list of every listed Now page.
+        pub async fn get_now_garden(&self) -> Result<RequestResponse<NowGardenResponse>, OmglolError> {
+            self.send_request::<NowGardenResponse>(false, Method::GET, route!("now/garden"), None)
+                .await
+        }
+
+        /// Get every status posted to the statuslog, by every address.
+        pub async fn get_statuslog(&self) -> Result<RequestResponse<StatuslogTimeline>, OmglolError> {
+            self.send_request::<StatuslogTimeline>(false, Method::GET, route!("statuslog"), None)
+                .await
+        }
+
+        /// Get the latest status of every address on the statuslog.
+        pub async fn get_statuslog_latest(
+            &self,
+        ) -> Result<RequestResponse<StatuslogTimeline>, OmglolError> {
+            self.send_request::<StatuslogTimeline>(
+                false,
+                Method::GET,
+                route!("statuslog/latest"),
+                None,
+            )
+            .await
+        }
     }
+}
 
-    pub async fn get_all_statuses(
-        &self,
-        address: &str,
-    ) -> Result<RequestResponse<StatuslogAllStatuses>, Box<dyn std::error::Error>> {
-        self.send_request::<StatuslogAllStatuses>(
-            false,
-            Method::GET,
-            format!("address/{}/statuses", &address).as_ref(),
-            None,
-        )
-        .await
+impl Default for OmglolClient<NoAuth> {
+    fn default() -> OmglolClient<NoAuth> {
+        OmglolClient::new()
     }
 }
 
@@ -535,42 +929,279 @@ impl<State> OmglolClient<State> {
         &self,
         authenticate: bool,
         method: Method,
-        uri: &str,
+        route: Route,
         body: Option<String>,
-    ) -> Result<RequestResponse<T>, Box<dyn std::error::Error>>
+    ) -> Result<RequestResponse<T>, OmglolError>
     where
         T: DeserializeOwned,
     {
-        let reqwest_client = &self.client;
-        let mut req = reqwest_client.request(method, api_endpoint!(uri));
+        let RawResponse { body, metadata } = self
+            .send_raw(authenticate, method.clone(), &route, body)
+            .await?;
 
-        if authenticate {
-            req = req.bearer_auth(&self.api_key.as_ref().unwrap().to_string());
-        }
+        let decoded = if body.trim().is_empty() {
+            // Some write endpoints answer `204 No Content`: there is no
+            // envelope, so build one around an empty response.
+            serde_json::from_value::<T>(serde_json::json!({})).map(|response| RequestResponse {
+                request: RequestStatus {
+                    status_code: metadata.status_code,
+                    success: true,
+                },
+                response,
+                metadata: ResponseMetadata::default(),
+            })
+        } else {
+            serde_json::from_str::<RequestResponse<T>>(&body)
+        };
+
+        let mut response = decoded.map_err(|source| OmglolError::Decode {
+            method,
+            path: route.path,
+            source,
+            body,
+        })?;
+        response.metadata = metadata;
+        Ok(response)
+    }
+
+    /// Send a request and return the raw body and metadata of a successful response,
+    /// retrying according to the client's `RetryPolicy`.
+    pub(crate) async fn send_raw(
+        &self,
+        authenticate: bool,
+        method: Method,
+        route: &Route,
+        body: Option<String>,
+    ) -> Result<RawResponse, OmglolError> {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
 
-        if body.is_some() {
-            req = req.body(body.unwrap());
+            let span = telemetry::request_span(&method, route);
+            let started = Instant::now();
+            let result = self
+                .send_with_retries(authenticate, method, route, body)
+                .instrument(span.clone())
+                .await;
+            telemetry::record_outcome(&span, &result, started);
+            result
         }
 
-        #[cfg(debug_assertions)]
-        dbg!(&req);
+        #[cfg(not(feature = "tracing"))]
+        self.send_with_retries(authenticate, method, route, body)
+            .await
+    }
+
+    async fn send_with_retries(
+        &self,
+        authenticate: bool,
+        method: Method,
+        route: &Route,
+        body: Option<String>,
+    ) -> Result<RawResponse, OmglolError> {
+        let may_retry = route.retry && self.retry_policy.allows(&method, &route.path);
+        let started = Instant::now();
+        let mut attempt = 1;
+
+        loop {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire(&route.path).await;
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::Span::current().record("attempts", attempt);
 
-        let resp = req.send().await?;
+            let (error, retry_after) = match self
+                .send_once(authenticate, method.clone(), route, body.clone())
+                .await
+            {
+                Ok(mut raw_res) => {
+                    raw_res.metadata.duration = started.elapsed();
+                    raw_res.metadata.attempts = attempt;
+                    return Ok(raw_res);
+                }
+                Err(failure) => failure,
+            };
 
-        let raw_res = match &resp.status().as_u16() {
-            _status_code @ 200 => resp.text().await?,
-            status_code => {
-                return Err(Box::new(RequestError {
-                    status_code: *status_code,
-                }))
+            if !may_retry
+                || !self.retry_policy.has_attempts_left(attempt)
+                || !retry::is_retryable(&error)
+            {
+                return Err(error);
             }
+
+            let delay = self.retry_policy.delay(attempt, retry_after);
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                attempt,
+                delay_ms = delay.as_millis() as u64,
+                error = %telemetry::redacted(&error),
+                "retrying request"
+            );
+            Delay::new(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Make a single attempt at a request. Failures come with the delay the
+    /// server asked for in `Retry-After`, if any.
+    async fn send_once(
+        &self,
+        authenticate: bool,
+        method: Method,
+        route: &Route,
+        body: Option<String>,
+    ) -> Result<RawResponse, (OmglolError, Option<Duration>)> {
+        let mut headers = HeaderMap::new();
+
+        if authenticate {
+            let api_key = match &self.credentials {
+                Some(credentials) => credentials.api_key().map_err(|error| (error, None))?,
+                None => None,
+            };
+            let api_key = api_key
+                .filter(|api_key| !api_key.trim().is_empty())
+                .ok_or_else(|| {
+                    let error = OmglolError::MissingApiKey {
+                        method: method.clone(),
+                        path: route.path.clone(),
+                    };
+                    (error, None)
+                })?;
+            let mut authorization =
+                HeaderValue::from_str(&format!("Bearer {}", api_key)).map_err(|_| {
+                    let error =
+                        OmglolError::Validation("API key is not a valid header value".to_string());
+                    (error, None)
+                })?;
+            authorization.set_sensitive(true);
+            headers.insert(AUTHORIZATION, authorization);
+        }
+
+        let request = HttpRequest {
+            method,
+            url: api_endpoint!(self.base_url, route.path_and_query()),
+            path: route.encoded_path.clone(),
+            headers,
+            body,
         };
+        let resp = self
+            .transport
+            .send(request)
+            .await
+            .map_err(|error| (error, None))?;
 
-        #[cfg(debug_assertions)]
-        dbg!(&raw_res);
+        if !(200..300).contains(&resp.status_code) {
+            let retry_after = retry::retry_after(&resp.headers);
+            return Err((
+                OmglolError::from_status(resp.status_code, &resp.body),
+                retry_after,
+            ));
+        }
 
-        let res: RequestResponse<T> = serde_json::from_str(&raw_res).unwrap();
+        Ok(RawResponse {
+            body: resp.body,
+            metadata: ResponseMetadata {
+                status_code: resp.status_code,
+                headers: resp.headers,
+                ..ResponseMetadata::default()
+            },
+        })
+    }
+}
+
+/// Path of a request together with the template it was built from, so the
+/// template can be reported without the member's data in it.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+pub(crate) struct Route {
+    pub template: &'static str,
+
+    /// Path with the values as given, reported in errors and traces
+    pub path: String,
+
+    /// Path with the values percent-encoded, as sent
+    pub encoded_path: String,
 
-        Ok(res)
+    /// Query string, only ever put in the request URL: it is not passed to
+    /// retry hooks, the rate limiter or errors, as it may hold secrets.
+    pub query: Option<String>,
+
+    /// Whether the retry policy applies to the request at all
+    pub retry: bool,
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+impl Route {
+    pub(crate) fn new(template: &'static str, path: String) -> Route {
+        Route {
+            template,
+            encoded_path: path.clone(),
+            path,
+            query: None,
+            retry: true,
+        }
     }
+
+    /// Send the request to `encoded_path`, the percent-encoded `path`.
+    pub(crate) fn encoded_path(mut self, encoded_path: String) -> Route {
+        self.encoded_path = encoded_path;
+        self
+    }
+
+    /// Send `query` along with the path.
+    pub(crate) fn query(mut self, query: String) -> Route {
+        self.query = Some(query);
+        self
+    }
+
+    /// Never retry the request, whatever the retry policy says.
+    pub(crate) fn single_attempt(mut self) -> Route {
+        self.retry = false;
+        self
+    }
+
+    /// Encoded path with the query string, if any.
+    fn path_and_query(&self) -> String {
+        match &self.query {
+            Some(query) => format!("{}?{}", self.encoded_path, query),
+            None => self.encoded_path.clone(),
+        }
+    }
+
+    /// Address the request is about, if the route is under `address/`.
+    pub(crate) fn address(&self) -> Option<&str> {
+        self.path
+            .strip_prefix("address/")
+            .and_then(|rest| rest.split('/').next())
+    }
+}
+
+/// Characters escaped in a path segment: the URL path set, plus `/` and `%`
+/// so a value cannot end its segment or read as an escape.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Percent-encode `value` for use as (part of) a path segment.
+fn encode_segment(value: &str) -> String {
+    utf8_percent_encode(value, PATH_SEGMENT).to_string()
+}
+
+/// Successful response before it is decoded
+pub(crate) struct RawResponse {
+    pub body: String,
+    pub metadata: ResponseMetadata,
 }
@@ -0,0 +1,223 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Credentials - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Sources of the bearer token used by an authenticated `OmglolClient`.
+//!
+//! The token is asked for before every request, so keys can be rotated
+//! without rebuilding clients:
+//!
+//! ```rust
+//! use omglol::{credentials::EnvVar, OmglolClient};
+//!
+//! let client = OmglolClient::new().auth_with(EnvVar::new());
+//! ```
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    time::SystemTime,
+};
+
+use crate::error::OmglolError;
+use crate::structures::Token;
+
+/// Environment variable read by [`EnvVar::new`]
+pub const API_KEY_VARIABLE: &str = "OMGLOL_API_KEY";
+
+/// Provides the bearer token for authenticated requests.
+///
+/// `api_key` is called synchronously before every request, on the thread
+/// running the client's future, so it blocks that executor thread while it
+/// runs. Return a value that is at hand (as the providers of this module do)
+/// and fetch or refresh tokens elsewhere, e.g. with [`OAuthToken::refresh`].
+pub trait CredentialProvider: Send + Sync {
+    /// Token for the next request, or `None` if there is none.
+    fn api_key(&self) -> Result<Option<String>, OmglolError>;
+}
+
+/// Closures returning the current token are providers too.
+impl<F> CredentialProvider for F
+where
+    F: Fn() -> Result<Option<String>, OmglolError> + Send + Sync,
+{
+    fn api_key(&self) -> Result<Option<String>, OmglolError> {
+        self()
+    }
+}
+
+/// A fixed API key, as used by [`crate::OmglolClient::auth`].
+#[derive(Clone)]
+pub struct StaticKey(String);
+
+impl StaticKey {
+    /// Create a new `StaticKey`.
+    pub fn new(api_key: impl Into<String>) -> StaticKey {
+        StaticKey(api_key.into())
+    }
+}
+
+impl CredentialProvider for StaticKey {
+    fn api_key(&self) -> Result<Option<String>, OmglolError> {
+        Ok(Some(self.0.clone()))
+    }
+}
+
+/// API key read from an environment variable on every request.
+#[derive(Debug, Clone)]
+pub struct EnvVar {
+    name: String,
+}
+
+impl EnvVar {
+    /// Read the key from `OMGLOL_API_KEY`.
+    pub fn new() -> EnvVar {
+        EnvVar::named(API_KEY_VARIABLE)
+    }
+
+    /// Read the key from the environment variable `name`.
+    pub fn named(name: impl Into<String>) -> EnvVar {
+        EnvVar { name: name.into() }
+    }
+}
+
+impl Default for EnvVar {
+    fn default() -> EnvVar {
+        EnvVar::new()
+    }
+}
+
+impl CredentialProvider for EnvVar {
+    fn api_key(&self) -> Result<Option<String>, OmglolError> {
+        Ok(env::var(&self.name).ok())
+    }
+}
+
+/// API key read from a configuration file.
+///
+/// The key is cached and the file only read again once its modification
+/// time or size changes, so each request costs a `stat` rather than a read.
+/// The file either holds the key alone, or an `api_key` or
+/// `OMGLOL_API_KEY` entry, as written in a TOML or `.env` file:
+///
+/// ```text
+/// # ~/.config/omglol
+/// api_key = "YOUR_API_KEY"
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigFile {
+    path: PathBuf,
+    cache: Arc<Mutex<Option<CachedKey>>>,
+}
+
+/// Key read from a `ConfigFile`, with the file version it came from
+#[derive(Debug)]
+struct CachedKey {
+    modified: SystemTime,
+    len: u64,
+    api_key: Option<String>,
+}
+
+impl ConfigFile {
+    /// Create a new `ConfigFile` reading from `path`.
+    pub fn new(path: impl Into<PathBuf>) -> ConfigFile {
+        ConfigFile {
+            path: path.into(),
+            cache: Arc::default(),
+        }
+    }
+
+    fn read(&self) -> Result<Option<String>, OmglolError> {
+        let config = fs::read_to_string(&self.path)?;
+        let lines: Vec<&str> = config
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        let entry = lines.iter().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            (key.eq_ignore_ascii_case("api_key") || key == API_KEY_VARIABLE).then(|| {
+                value
+                    .trim()
+                    .trim_matches(|c| c == '"' || c == '\'')
+                    .to_string()
+            })
+        });
+
+        Ok(match (entry, lines.as_slice()) {
+            (Some(api_key), _) => Some(api_key),
+            (None, [api_key]) if !api_key.contains('=') => Some(api_key.to_string()),
+            _ => None,
+        })
+    }
+}
+
+impl CredentialProvider for ConfigFile {
+    fn api_key(&self) -> Result<Option<String>, OmglolError> {
+        let metadata = fs::metadata(&self.path)?;
+        let Ok(modified) = metadata.modified() else {
+            // No modification times on this platform: always read.
+            return self.read();
+        };
+
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(cached) = cache.as_ref() {
+            if cached.modified == modified && cached.len == metadata.len() {
+                return Ok(cached.api_key.clone());
+            }
+        }
+
+        let api_key = self.read()?;
+        *cache = Some(CachedKey {
+            modified,
+            len: metadata.len(),
+            api_key: api_key.clone(),
+        });
+        Ok(api_key)
+    }
+}
+
+/// OAuth access token that can be replaced while clients use it.
+///
+/// Clones share the same token, so refreshing one refreshes every client.
+#[derive(Debug, Clone)]
+pub struct OAuthToken {
+    token: Arc<RwLock<Token>>,
+}
+
+impl OAuthToken {
+    /// Create a new `OAuthToken`.
+    pub fn new(token: Token) -> OAuthToken {
+        OAuthToken {
+            token: Arc::new(RwLock::new(token)),
+        }
+    }
+
+    /// Replace the token used by every client sharing this provider.
+    pub fn refresh(&self, token: Token) {
+        *self.token.write().unwrap() = token;
+    }
+
+    /// The current token.
+    pub fn token(&self) -> Token {
+        self.token.read().unwrap().clone()
+    }
+}
+
+impl CredentialProvider for OAuthToken {
+    fn api_key(&self) -> Result<Option<String>, OmglolError> {
+        Ok(Some(self.token.read().unwrap().access_token.clone()))
+    }
+}
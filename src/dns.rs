@@ -0,0 +1,53 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+DNS - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Helpers to manage an address' DNS records
+
+pub mod sync;
+pub mod zone;
+
+use crate::structures::DNStype;
+
+/// Name of a DNS record relative to `address`, as used when creating records.
+///
+/// The API reports record names relative to the omg.lol zone (e.g.
+/// `www.foobar` for `www.foobar.omg.lol`), while new records are named
+/// relative to the address (e.g. `www`). The address itself, `@` and the
+/// empty string all map to `""`.
+pub fn relative_name(name: &str, address: &str) -> String {
+    let name = name.trim().trim_end_matches('.').to_ascii_lowercase();
+    let name = name.strip_suffix(".omg.lol").unwrap_or(&name);
+    let address = address.trim().to_ascii_lowercase();
+
+    if name == "@" || name == address {
+        return String::new();
+    }
+
+    match name.strip_suffix(&format!(".{}", address)) {
+        Some(subdomain) => subdomain.to_string(),
+        None => name.to_string(),
+    }
+}
+
+/// Compare the data of two records of type `record_type`.
+///
+/// Host names are compared case-insensitively and without their trailing dot.
+pub(crate) fn same_data(record_type: &DNStype, a: &str, b: &str) -> bool {
+    match record_type {
+        DNStype::CNAME | DNStype::MX | DNStype::NS | DNStype::SRV => a
+            .trim()
+            .trim_end_matches('.')
+            .eq_ignore_ascii_case(b.trim().trim_end_matches('.')),
+        _ => a.trim() == b.trim(),
+    }
+}
@@ -0,0 +1,297 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+DNS sync - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Declarative DNS zone management.
+//!
+//! Describe the records an address should have, compare them with the live
+//! zone and apply only the difference:
+//!
+//! ```rust,no_run
+//! use omglol::{dns::sync, structures::{DNSrecordBuilder, DNStype}, OmglolClient};
+//!
+//! # async fn run() -> Result<(), omglol::OmglolError> {
+//! let client = OmglolClient::new().auth("YOUR_API_KEY".to_string());
+//! let desired = vec![
+//!     DNSrecordBuilder::new(DNStype::A, "".to_string(), "192.0.2.1".to_string()),
+//!     DNSrecordBuilder::new(DNStype::CNAME, "www".to_string(), "foobar.omg.lol".to_string()),
+//! ];
+//!
+//! let plan = sync::plan(&client, "foobar", desired).await?;
+//! println!("{}", plan); // dry run
+//!
+//! let report = plan.apply(&client).await;
+//! assert!(report.is_success());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use crate::client::{Auth, OmglolClient};
+use crate::dns::{relative_name, same_data};
+use crate::error::OmglolError;
+use crate::structures::{DNSrecord, DNSrecordBuilder};
+
+/// A single step of a `SyncPlan`
+#[derive(Debug, Clone)]
+pub enum SyncAction {
+    /// The record is missing from the zone and will be created.
+    Create(DNSrecordBuilder),
+
+    /// A record with the same type and name exists but differs.
+    Update {
+        current: DNSrecord,
+        desired: DNSrecordBuilder,
+    },
+
+    /// The record is not in the desired set and will be deleted.
+    Delete(DNSrecord),
+
+    /// The record already matches the desired one.
+    Unchanged(DNSrecord),
+}
+
+impl SyncAction {
+    /// `true` unless the action is `Unchanged`.
+    pub fn is_change(&self) -> bool {
+        !matches!(self, SyncAction::Unchanged(_))
+    }
+}
+
+/// Changes needed to turn an address' zone into the desired set of records
+#[derive(Debug, Clone)]
+pub struct SyncPlan {
+    /// Address the plan applies to
+    pub address: String,
+
+    /// Planned actions, one per desired or existing record
+    pub actions: Vec<SyncAction>,
+}
+
+impl SyncPlan {
+    /// Compute the plan turning `current` into `desired`, without any requests.
+    ///
+    /// Desired records are matched to existing ones by type, name and data
+    /// (`Unchanged`), then by type and name (`Update`). What is left over is
+    /// created or deleted. A desired record without a priority or TTL matches
+    /// any priority or TTL.
+    pub fn new<I, R>(address: &str, current: &[DNSrecord], desired: I) -> SyncPlan
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<DNSrecordBuilder>,
+    {
+        let desired: Vec<DNSrecordBuilder> = desired.into_iter().map(Into::into).collect();
+        let mut remaining: Vec<Option<&DNSrecord>> = current.iter().map(Some).collect();
+        let mut matched: Vec<Option<SyncAction>> = vec![None; desired.len()];
+
+        for (i, wanted) in desired.iter().enumerate() {
+            if let Some(slot) = remaining
+                .iter_mut()
+                .find(|slot| slot.is_some_and(|record| matches(address, record, wanted)))
+            {
+                matched[i] = slot.take().cloned().map(SyncAction::Unchanged);
+            }
+        }
+
+        for (i, wanted) in desired.iter().enumerate() {
+            if matched[i].is_some() {
+                continue;
+            }
+
+            let slot = remaining
+                .iter_mut()
+                .find(|slot| slot.is_some_and(|record| same_key(address, record, wanted)));
+
+            matched[i] = Some(match slot.and_then(|slot| slot.take()) {
+                Some(record) => SyncAction::Update {
+                    current: record.clone(),
+                    desired: wanted.clone(),
+                },
+                None => SyncAction::Create(wanted.clone()),
+            });
+        }
+
+        let mut actions: Vec<SyncAction> = matched.into_iter().flatten().collect();
+        actions.extend(
+            remaining
+                .into_iter()
+                .flatten()
+                .map(|record| SyncAction::Delete(record.clone())),
+        );
+
+        SyncPlan {
+            address: address.to_string(),
+            actions,
+        }
+    }
+
+    /// `true` if applying the plan would change the zone.
+    pub fn has_changes(&self) -> bool {
+        self.actions.iter().any(SyncAction::is_change)
+    }
+
+    /// Apply the plan, continuing past failed records.
+    ///
+    /// Deletions run first so that replaced records (e.g. a `CNAME`) do not
+    /// conflict with the ones being created.
+    pub async fn apply(&self, client: &OmglolClient<Auth>) -> SyncReport {
+        let mut results = Vec::new();
+
+        let ordered = self
+            .actions
+            .iter()
+            .filter(|action| matches!(action, SyncAction::Delete(_)))
+            .chain(
+                self.actions
+                    .iter()
+                    .filter(|action| !matches!(action, SyncAction::Delete(_))),
+            );
+
+        for action in ordered {
+            let result = match action {
+                SyncAction::Create(desired) => client
+                    .create_dns_record(&self.address, desired)
+                    .await
+                    .map(|_| ()),
+                SyncAction::Update { current, desired } => client
+                    .update_dns_record(&self.address, current.id, desired)
+                    .await
+                    .map(|_| ()),
+                SyncAction::Delete(current) => client
+                    .delete_dns_record(&self.address, current.id)
+                    .await
+                    .map(|_| ()),
+                SyncAction::Unchanged(_) => continue,
+            };
+
+            results.push(SyncResult {
+                action: action.clone(),
+                result,
+            });
+        }
+
+        SyncReport { results }
+    }
+}
+
+/// Dry-run output, one line per action:
+/// `+` create, `~` update, `-` delete, `=` unchanged.
+impl fmt::Display for SyncPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "DNS plan for {}:", self.address)?;
+
+        for action in &self.actions {
+            match action {
+                SyncAction::Create(desired) => {
+                    writeln!(f, "  + {}", describe_builder(&self.address, desired))?
+                }
+                SyncAction::Update { current, desired } => writeln!(
+                    f,
+                    "  ~ {} -> {}",
+                    describe_record(&self.address, current),
+                    describe_builder(&self.address, desired)
+                )?,
+                SyncAction::Delete(current) => {
+                    writeln!(f, "  - {}", describe_record(&self.address, current))?
+                }
+                SyncAction::Unchanged(current) => {
+                    writeln!(f, "  = {}", describe_record(&self.address, current))?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome of applying one action of a `SyncPlan`
+#[derive(Debug)]
+pub struct SyncResult {
+    pub action: SyncAction,
+    pub result: Result<(), OmglolError>,
+}
+
+/// Per-record outcome of `SyncPlan::apply`
+#[derive(Debug)]
+pub struct SyncReport {
+    /// One entry per applied change, in the order they were sent
+    pub results: Vec<SyncResult>,
+}
+
+impl SyncReport {
+    /// `true` if every change was applied.
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|result| result.result.is_ok())
+    }
+
+    /// Changes that could not be applied.
+    pub fn failures(&self) -> impl Iterator<Item = &SyncResult> {
+        self.results.iter().filter(|result| result.result.is_err())
+    }
+}
+
+/// Fetch the zone of `address` and compute the plan towards `desired`.
+pub async fn plan<I, R>(
+    client: &OmglolClient<Auth>,
+    address: &str,
+    desired: I,
+) -> Result<SyncPlan, OmglolError>
+where
+    I: IntoIterator<Item = R>,
+    R: Into<DNSrecordBuilder>,
+{
+    let current = client.get_dns_records(address).await?.response.dns;
+    Ok(SyncPlan::new(address, &current, desired))
+}
+
+fn same_key(address: &str, record: &DNSrecord, wanted: &DNSrecordBuilder) -> bool {
+    record.record_type == wanted.record_type
+        && relative_name(&record.name, address) == relative_name(&wanted.name, address)
+}
+
+fn matches(address: &str, record: &DNSrecord, wanted: &DNSrecordBuilder) -> bool {
+    same_key(address, record, wanted)
+        && same_data(&record.record_type, &record.data, &wanted.data)
+        && wanted.ttl.is_none_or(|ttl| ttl == record.ttl)
+        && wanted
+            .priority
+            .is_none_or(|priority| Some(priority) == record.priority)
+}
+
+fn display_name(name: String) -> String {
+    if name.is_empty() {
+        "@".to_string()
+    } else {
+        name
+    }
+}
+
+fn describe_record(address: &str, record: &DNSrecord) -> String {
+    describe_builder(address, &DNSrecordBuilder::from(record))
+}
+
+fn describe_builder(address: &str, record: &DNSrecordBuilder) -> String {
+    let mut description = format!(
+        "{} {}",
+        record.record_type,
+        display_name(relative_name(&record.name, address))
+    );
+    if let Some(ttl) = record.ttl {
+        description.push_str(&format!(" ttl={}", ttl));
+    }
+    if let Some(priority) = record.priority {
+        description.push_str(&format!(" priority={}", priority));
+    }
+    description.push_str(&format!(" {:?}", record.data));
+    description
+}
@@ -0,0 +1,412 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+DNS zone files - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Import and export DNS records as [RFC 1035](https://www.rfc-editor.org/rfc/rfc1035#section-5)
+//! zone files.
+//!
+//! ```rust
+//! use omglol::dns::zone;
+//!
+//! let zone_file = "$TTL 3600\n@ IN MX 10 mx.example.com.\nwww IN CNAME @\n";
+//! let records = zone::parse("foobar", zone_file).unwrap();
+//!
+//! assert_eq!(records[0].priority, Some(10));
+//! assert_eq!(records[1].data, "foobar.omg.lol");
+//! ```
+
+use std::fmt::Write;
+
+use crate::dns::relative_name;
+use crate::error::OmglolError;
+use crate::structures::{DNSrecord, DNSrecordBuilder, DNStype};
+
+/// Longest string allowed inside a single TXT character-string.
+const TXT_CHUNK_LENGTH: usize = 255;
+
+/// Render `records` of `address` as a zone file with origin
+/// `<address>.omg.lol.`.
+///
+/// Host names in record data are written fully qualified, TXT data is quoted
+/// and split in 255 byte strings.
+pub fn export(address: &str, records: &[DNSrecord]) -> String {
+    let mut zone = String::new();
+    let _ = writeln!(zone, "; omg.lol DNS records for {}", address);
+    let _ = writeln!(zone, "$ORIGIN {}.", origin(address));
+
+    for record in records {
+        let name = match relative_name(&record.name, address) {
+            name if name.is_empty() => "@".to_string(),
+            name => name,
+        };
+
+        let _ = writeln!(
+            zone,
+            "{}\t{}\tIN\t{}\t{}",
+            name,
+            record.ttl,
+            record.record_type,
+            export_data(record)
+        );
+    }
+
+    zone
+}
+
+/// Parse a zone file into records for `address`, ready to be created.
+///
+/// Supports `$ORIGIN` and `$TTL` directives, comments, parentheses spanning
+/// several lines, omitted owners, TTLs and classes, and TTL units
+/// (e.g. `1h`). Owner names are made relative to the address and host names
+/// in record data are made fully qualified, without the trailing dot.
+///
+/// The `SOA` record and the `NS` records of the address itself are managed
+/// by omg.lol, so they are skipped rather than returned.
+pub fn parse(address: &str, zone: &str) -> Result<Vec<DNSrecordBuilder>, OmglolError> {
+    let mut origin = format!("{}.", origin(address));
+    let mut default_ttl = None;
+    let mut last_owner: Option<String> = None;
+    let mut records = Vec::new();
+
+    for (line_number, starts_blank, tokens) in logical_lines(zone)? {
+        let invalid = |reason: String| {
+            OmglolError::Validation(format!("zone file line {}: {}", line_number, reason))
+        };
+
+        let mut tokens = tokens.into_iter().peekable();
+
+        let owner = if starts_blank {
+            last_owner
+                .clone()
+                .ok_or_else(|| invalid("record without an owner name".to_string()))?
+        } else {
+            let first = tokens.next().expect("logical lines are never empty");
+            match first.text.to_ascii_uppercase().as_str() {
+                "$ORIGIN" => {
+                    let name = tokens
+                        .next()
+                        .ok_or_else(|| invalid("$ORIGIN without a name".to_string()))?;
+                    origin = absolute_name(&name.text, &origin);
+                    continue;
+                }
+                "$TTL" => {
+                    let ttl = tokens
+                        .next()
+                        .ok_or_else(|| invalid("$TTL without a value".to_string()))?;
+                    default_ttl = Some(parse_ttl(&ttl.text).map_err(invalid)?);
+                    continue;
+                }
+                directive if directive.starts_with('$') => {
+                    return Err(invalid(format!("unsupported directive {}", first.text)))
+                }
+                _ => absolute_name(&first.text, &origin),
+            }
+        };
+        last_owner = Some(owner.clone());
+
+        let mut ttl = default_ttl;
+        let record_type = loop {
+            let token = tokens
+                .next()
+                .ok_or_else(|| invalid("missing record type".to_string()))?;
+
+            if token.text.eq_ignore_ascii_case("IN") {
+                continue;
+            }
+            if token.text.starts_with(|c: char| c.is_ascii_digit()) {
+                ttl = Some(parse_ttl(&token.text).map_err(invalid)?);
+                continue;
+            }
+            break token.text.parse::<DNStype>()?;
+        };
+
+        let rdata: Vec<Token> = tokens.collect();
+        if rdata.is_empty() {
+            return Err(invalid(format!("{} record without data", record_type)));
+        }
+
+        let (priority, data) = match record_type {
+            DNStype::MX | DNStype::SRV => {
+                let priority = rdata[0]
+                    .text
+                    .parse::<i32>()
+                    .map_err(|_| invalid(format!("invalid priority {:?}", rdata[0].text)))?;
+                let mut rest: Vec<String> = rdata[1..].iter().map(|t| t.text.clone()).collect();
+                if let Some(target) = rest.last_mut() {
+                    *target = host_name(target, &origin);
+                }
+                (Some(priority), rest.join(" "))
+            }
+            DNStype::CNAME | DNStype::NS => (None, host_name(&rdata[0].text, &origin)),
+            DNStype::TXT => (None, rdata.iter().map(|t| t.text.as_str()).collect()),
+            _ => (
+                None,
+                rdata
+                    .iter()
+                    .map(Token::quoted_text)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+        };
+
+        let name = relative_name(owner.trim_end_matches('.'), address);
+        if is_managed(&record_type, &name) {
+            continue;
+        }
+
+        let mut record = DNSrecordBuilder::new(record_type, name, data);
+        record.priority = priority;
+        record.ttl = ttl;
+        record
+            .validate()
+            .map_err(|error| invalid(error.to_string()))?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Records omg.lol serves itself for every address.
+fn is_managed(record_type: &DNStype, name: &str) -> bool {
+    match record_type {
+        DNStype::Other(record_type) => record_type.eq_ignore_ascii_case("SOA"),
+        DNStype::NS => name.is_empty(),
+        _ => false,
+    }
+}
+
+fn origin(address: &str) -> String {
+    format!("{}.omg.lol", address)
+}
+
+fn export_data(record: &DNSrecord) -> String {
+    let priority = record.priority.unwrap_or(0);
+
+    match record.record_type {
+        DNStype::CNAME | DNStype::NS => fully_qualified(&record.data),
+        DNStype::MX => format!("{} {}", priority, fully_qualified(&record.data)),
+        DNStype::SRV => {
+            let mut fields: Vec<String> =
+                record.data.split_whitespace().map(str::to_string).collect();
+            if let Some(target) = fields.last_mut() {
+                *target = fully_qualified(target);
+            }
+            format!("{} {}", priority, fields.join(" "))
+        }
+        DNStype::TXT => quote_txt(&record.data),
+        _ => record.data.clone(),
+    }
+}
+
+fn fully_qualified(name: &str) -> String {
+    if name.ends_with('.') {
+        name.to_string()
+    } else {
+        format!("{}.", name)
+    }
+}
+
+/// Quote TXT data, splitting it in character-strings of at most 255 bytes.
+fn quote_txt(data: &str) -> String {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+
+    for c in data.chars() {
+        if chunk.len() + c.len_utf8() > TXT_CHUNK_LENGTH {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(c);
+    }
+    chunks.push(chunk);
+
+    chunks
+        .iter()
+        .map(|chunk| quote(chunk))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Resolve `name` against `origin`, returning it with a trailing dot.
+fn absolute_name(name: &str, origin: &str) -> String {
+    if name == "@" {
+        origin.to_string()
+    } else if name.ends_with('.') {
+        name.to_string()
+    } else {
+        format!("{}.{}", name, origin)
+    }
+}
+
+/// Fully qualified host name without the trailing dot, as the API stores it.
+fn host_name(name: &str, origin: &str) -> String {
+    absolute_name(name, origin)
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn parse_ttl(ttl: &str) -> Result<i32, String> {
+    let invalid = || format!("invalid TTL {:?}", ttl);
+    let mut total: i64 = 0;
+    let mut number = String::new();
+
+    for c in ttl.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 604800,
+            _ => return Err(invalid()),
+        };
+        total += number.parse::<i64>().map_err(|_| invalid())? * unit;
+        number.clear();
+    }
+
+    if !number.is_empty() {
+        total += number.parse::<i64>().map_err(|_| invalid())?;
+    }
+
+    i32::try_from(total).map_err(|_| invalid())
+}
+
+/// A word of a zone file line
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+impl Token {
+    fn quoted_text(&self) -> String {
+        if self.quoted {
+            quote(&self.text)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// Split a zone file into logical lines: `(line number, starts with
+/// whitespace, tokens)`, joining lines inside parentheses and dropping
+/// comments and empty lines.
+fn logical_lines(zone: &str) -> Result<Vec<(usize, bool, Vec<Token>)>, OmglolError> {
+    let mut lines = Vec::new();
+    let mut current: Option<(usize, bool, Vec<Token>)> = None;
+    let mut depth = 0;
+
+    for (index, line) in zone.lines().enumerate() {
+        let line_number = index + 1;
+        let invalid = |reason: &str| {
+            OmglolError::Validation(format!("zone file line {}: {}", line_number, reason))
+        };
+
+        let (_, _, tokens) =
+            current.get_or_insert_with(|| (line_number, line.starts_with([' ', '\t']), Vec::new()));
+
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                ';' => break,
+                '(' => depth += 1,
+                ')' if depth == 0 => return Err(invalid("unbalanced parenthesis")),
+                ')' => depth -= 1,
+                '"' => {
+                    let mut text = Vec::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => unescape(&mut chars, &mut text).map_err(invalid)?,
+                            Some(c) => push_char(&mut text, c),
+                            None => return Err(invalid("unterminated quoted string")),
+                        }
+                    }
+                    tokens.push(Token {
+                        text: utf8(text).map_err(invalid)?,
+                        quoted: true,
+                    });
+                }
+                c if c.is_whitespace() => {}
+                c => {
+                    let mut text = Vec::new();
+                    let mut next = Some(c);
+                    while let Some(c) = next {
+                        if c == '\\' {
+                            unescape(&mut chars, &mut text).map_err(invalid)?;
+                        } else {
+                            push_char(&mut text, c);
+                        }
+                        next = chars.next_if(|c| !c.is_whitespace() && !"();\"".contains(*c));
+                    }
+                    tokens.push(Token {
+                        text: utf8(text).map_err(invalid)?,
+                        quoted: false,
+                    });
+                }
+            }
+        }
+
+        if depth == 0 {
+            if let Some(line) = current.take().filter(|(_, _, tokens)| !tokens.is_empty()) {
+                lines.push(line);
+            }
+        }
+    }
+
+    if depth != 0 {
+        return Err(OmglolError::Validation(
+            "zone file ends inside parentheses".to_string(),
+        ));
+    }
+
+    Ok(lines)
+}
+
+/// Read what follows a backslash into `text`: either `\DDD` (a decimal
+/// byte, possibly part of a multi-byte UTF-8 character) or a literal
+/// character.
+fn unescape(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    text: &mut Vec<u8>,
+) -> Result<(), &'static str> {
+    let mut digits = String::new();
+    while digits.len() < 3 {
+        match chars.next_if(char::is_ascii_digit) {
+            Some(c) => digits.push(c),
+            None => break,
+        }
+    }
+
+    if digits.is_empty() {
+        push_char(text, chars.next().unwrap_or('\\'));
+        return Ok(());
+    }
+
+    let byte = digits.parse::<u8>().map_err(|_| "invalid \\DDD escape")?;
+    text.push(byte);
+    Ok(())
+}
+
+fn push_char(text: &mut Vec<u8>, c: char) {
+    text.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+fn utf8(text: Vec<u8>) -> Result<String, &'static str> {
+    String::from_utf8(text).map_err(|_| "escaped text is not valid UTF-8")
+}
@@ -11,30 +11,98 @@ This Source Code Form is subject to the terms of the Mozilla Public License, v.
  You can obtain one at https://mozilla.org/MPL/2.0/.
 */
 
-//! Helper to format email addresses in the format that the API expects
+//! Email forwarding for your @omg.lol address.
+
+pub mod bulk;
+
+use std::str::FromStr;
 
 use email_address::EmailAddress;
+use serde::Serialize;
+
+use crate::error::OmglolError;
+
+/// Body of a forwarding update, a comma-separated list of destinations
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ForwardingUpdate {
+    pub destination: String,
+}
+
+impl ForwardingUpdate {
+    /// Forward to `destinations`; an empty slice turns forwarding off.
+    pub fn new(destinations: &[EmailAddress]) -> ForwardingUpdate {
+        let destination = destinations
+            .iter()
+            .map(EmailAddress::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        ForwardingUpdate { destination }
+    }
+}
 
-/// Format a `Vec<EmailAddress>` to a String in the JSON format the API expects
+/// Format a list of forwarding addresses as the JSON body the API expects
 ///
 /// # Arguments
-/// * `addresses` - A `Vec<EmailAddress>` containing the forwarding email address(es)
+/// * `addresses` - the forwarding email address(es), possibly none
 /// # Output
-/// `String` in the JSON format the API endpoint expects
-pub fn format_addresses_string(addresses: &Vec<EmailAddress>) -> String {
-    // This code is will probably make any serious Rust developer hide in shame
-    // but the endpoint expects a weird format and... well, if it works...
-    let mut body_string = String::from(format!("{{\"destination\": \""));
-    body_string.push_str(&addresses[0].to_string().as_str());
-
-    if addresses.len() > 1 {
-        for address in addresses {
-            body_string.push_str(", ");
-            body_string.push_str(&address.as_str());
-        }
+/// `String` in the JSON format the API endpoint expects, or
+/// `OmglolError::Encode` if serialization fails
+pub fn format_addresses_string(addresses: &[EmailAddress]) -> Result<String, OmglolError> {
+    Ok(serde_json::to_string(&ForwardingUpdate::new(addresses))?)
+}
+
+/// Parse forwarding destinations, e.g. from a config file.
+///
+/// Fails with `OmglolError::Validation` naming the first invalid address.
+///
+/// Usage:
+/// ```rust
+/// use omglol::email::parse_destinations;
+///
+/// let destinations = parse_destinations(&["foo@example.com", "bar@example.com"]).unwrap();
+/// assert_eq!(destinations.len(), 2);
+/// assert!(parse_destinations(&["not an address"]).is_err());
+/// ```
+pub fn parse_destinations<S: AsRef<str>>(
+    destinations: &[S],
+) -> Result<Vec<EmailAddress>, OmglolError> {
+    destinations
+        .iter()
+        .map(|destination| {
+            let destination = destination.as_ref().trim();
+            EmailAddress::from_str(destination).map_err(|error| {
+                OmglolError::Validation(format!(
+                    "invalid forwarding address {:?}: {}",
+                    destination, error
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Check that `destinations` can be set as the forwarding addresses of
+/// `address`: there is at least one, and none is `address@omg.lol` itself.
+pub(crate) fn validate_destinations(
+    address: &str,
+    destinations: &[EmailAddress],
+) -> Result<(), OmglolError> {
+    if destinations.is_empty() {
+        return Err(OmglolError::Validation(
+            "no forwarding addresses given, use clear_forwarding_addresses to turn forwarding off"
+                .to_string(),
+        ));
     }
 
-    body_string.push_str("\"}");
+    let own_address = format!("{}@omg.lol", address);
+    if let Some(destination) = destinations
+        .iter()
+        .find(|destination| destination.as_str().eq_ignore_ascii_case(&own_address))
+    {
+        return Err(OmglolError::Validation(format!(
+            "{} would forward to itself",
+            destination
+        )));
+    }
 
-    return body_string;
+    Ok(())
 }
@@ -0,0 +1,279 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Bulk email forwarding - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Email forwarding for many addresses at once.
+//!
+//! Map each address to the inboxes it should forward to, compare with the
+//! current settings and update only the addresses that differ:
+//!
+//! ```rust,no_run
+//! use omglol::{email::{bulk, parse_destinations}, OmglolClient};
+//!
+//! # async fn run() -> Result<(), omglol::OmglolError> {
+//! let client = OmglolClient::new().auth("YOUR_API_KEY".to_string());
+//! let desired = vec![
+//!     ("foobar", parse_destinations(&["sales@example.com"])?),
+//!     ("barbaz", parse_destinations(&["support@example.com", "oncall@example.com"])?),
+//!     ("bazqux", Vec::new()), // stop forwarding
+//! ];
+//!
+//! let plan = bulk::plan(&client, desired).await;
+//! println!("{}", plan); // dry run
+//!
+//! let report = plan.apply(&client).await;
+//! for failure in report.failures() {
+//!     eprintln!("{}: {:?}", failure.address, failure.result);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requests for the different addresses run concurrently; configure a
+//! [`RateLimiter`](crate::rate_limit::RateLimiter) on the client to pace them.
+
+use std::fmt;
+
+use email_address::EmailAddress;
+use futures::future::join_all;
+
+use crate::client::{Auth, OmglolClient};
+use crate::email::validate_destinations;
+use crate::error::OmglolError;
+
+/// Current and desired forwarding of one address
+#[derive(Debug, Clone)]
+pub struct AddressPlan {
+    pub address: String,
+    pub current: Vec<EmailAddress>,
+
+    /// Destinations to set, none to turn forwarding off
+    pub desired: Vec<EmailAddress>,
+}
+
+impl AddressPlan {
+    /// `true` if the destinations differ, ignoring order, case and repeats.
+    pub fn is_change(&self) -> bool {
+        normalized(&self.current) != normalized(&self.desired)
+    }
+
+    /// Desired destinations that are not set yet.
+    pub fn added(&self) -> Vec<&EmailAddress> {
+        missing_from(&self.desired, &self.current)
+    }
+
+    /// Current destinations that will be removed.
+    pub fn removed(&self) -> Vec<&EmailAddress> {
+        missing_from(&self.current, &self.desired)
+    }
+}
+
+/// Forwarding changes for a set of addresses
+#[derive(Debug)]
+pub struct ForwardingPlan {
+    /// Addresses whose forwarding could be read, sorted by address
+    pub addresses: Vec<AddressPlan>,
+
+    /// Addresses that could not be planned, with the reason
+    pub failures: Vec<ForwardingResult>,
+}
+
+impl ForwardingPlan {
+    /// `true` if applying the plan would change any address.
+    pub fn has_changes(&self) -> bool {
+        self.addresses.iter().any(AddressPlan::is_change)
+    }
+
+    /// Update every address that changes, continuing past failures.
+    ///
+    /// The report has one entry per address, including unchanged ones and
+    /// those that could not be planned.
+    pub async fn apply(self, client: &OmglolClient<Auth>) -> ForwardingReport {
+        let updates = self.addresses.iter().map(|plan| async move {
+            let result = if !plan.is_change() {
+                Ok(())
+            } else if plan.desired.is_empty() {
+                client
+                    .clear_forwarding_addresses(&plan.address)
+                    .await
+                    .map(|_| ())
+            } else {
+                client
+                    .set_forwarding_addresses(&plan.address, &plan.desired)
+                    .await
+                    .map(|_| ())
+            };
+
+            ForwardingResult {
+                address: plan.address.clone(),
+                changed: plan.is_change(),
+                result,
+            }
+        });
+
+        let mut results = join_all(updates).await;
+        results.extend(self.failures);
+        results.sort_by(|a, b| a.address.cmp(&b.address));
+
+        ForwardingReport { results }
+    }
+}
+
+/// Dry-run output, one line per address:
+/// `~` update, `=` unchanged, `!` could not be planned.
+impl fmt::Display for ForwardingPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Email forwarding plan:")?;
+
+        for plan in &self.addresses {
+            if plan.is_change() {
+                writeln!(
+                    f,
+                    "  ~ {}: {} -> {}",
+                    plan.address,
+                    describe(&plan.current),
+                    describe(&plan.desired)
+                )?;
+            } else {
+                writeln!(f, "  = {}: {}", plan.address, describe(&plan.current))?;
+            }
+        }
+
+        for failure in &self.failures {
+            if let Err(error) = &failure.result {
+                writeln!(f, "  ! {}: {}", failure.address, error)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome for one address
+#[derive(Debug)]
+pub struct ForwardingResult {
+    pub address: String,
+
+    /// `true` if an update was sent
+    pub changed: bool,
+
+    pub result: Result<(), OmglolError>,
+}
+
+/// Per-address outcome of `ForwardingPlan::apply`
+#[derive(Debug)]
+pub struct ForwardingReport {
+    /// One entry per address, sorted by address
+    pub results: Vec<ForwardingResult>,
+}
+
+impl ForwardingReport {
+    /// `true` if every address is now forwarded as desired.
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|result| result.result.is_ok())
+    }
+
+    /// Addresses that could not be planned or updated.
+    pub fn failures(&self) -> impl Iterator<Item = &ForwardingResult> {
+        self.results.iter().filter(|result| result.result.is_err())
+    }
+
+    /// Addresses that were updated.
+    pub fn changed(&self) -> impl Iterator<Item = &ForwardingResult> {
+        self.results
+            .iter()
+            .filter(|result| result.changed && result.result.is_ok())
+    }
+}
+
+/// Fetch the forwarding of every address in `desired` concurrently and plan
+/// the changes towards it.
+///
+/// Arguments:
+/// * `client` - authenticated client owning the addresses
+/// * `desired` - address to destinations pairs, e.g. a `HashMap`. No
+///   destinations means forwarding is turned off.
+pub async fn plan<I, A>(client: &OmglolClient<Auth>, desired: I) -> ForwardingPlan
+where
+    I: IntoIterator<Item = (A, Vec<EmailAddress>)>,
+    A: Into<String>,
+{
+    let fetches = desired.into_iter().map(|(address, desired)| async move {
+        let address: String = address.into();
+        if !desired.is_empty() {
+            validate_destinations(&address, &desired).map_err(|error| (address.clone(), error))?;
+        }
+
+        match client.get_forwarding_addresses(&address).await {
+            Ok(response) => Ok(AddressPlan {
+                address,
+                current: response.response.destination_array,
+                desired,
+            }),
+            Err(error) => Err((address, error)),
+        }
+    });
+
+    let mut plan = ForwardingPlan {
+        addresses: Vec::new(),
+        failures: Vec::new(),
+    };
+    for fetched in join_all(fetches).await {
+        match fetched {
+            Ok(address_plan) => plan.addresses.push(address_plan),
+            Err((address, error)) => plan.failures.push(ForwardingResult {
+                address,
+                changed: false,
+                result: Err(error),
+            }),
+        }
+    }
+    plan.addresses.sort_by(|a, b| a.address.cmp(&b.address));
+    plan.failures.sort_by(|a, b| a.address.cmp(&b.address));
+    plan
+}
+
+fn normalized(destinations: &[EmailAddress]) -> Vec<String> {
+    let mut normalized: Vec<String> = destinations
+        .iter()
+        .map(|destination| destination.as_str().to_ascii_lowercase())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+fn missing_from<'a>(
+    destinations: &'a [EmailAddress],
+    other: &[EmailAddress],
+) -> Vec<&'a EmailAddress> {
+    destinations
+        .iter()
+        .filter(|destination| {
+            !other
+                .iter()
+                .any(|o| o.as_str().eq_ignore_ascii_case(destination.as_str()))
+        })
+        .collect()
+}
+
+fn describe(destinations: &[EmailAddress]) -> String {
+    if destinations.is_empty() {
+        "(none)".to_string()
+    } else {
+        destinations
+            .iter()
+            .map(EmailAddress::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
@@ -0,0 +1,198 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Error - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Errors returned by `OmglolClient`
+
+use reqwest::Method;
+use std::{error::Error, fmt};
+
+/// How many characters of a response body are shown when displaying a
+/// `Decode` error.
+const BODY_SNIPPET_LENGTH: usize = 200;
+
+/// Everything that can go wrong when talking to api.omg.lol
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum OmglolError {
+    /// The request could not be sent or its response could not be read.
+    Transport(reqwest::Error),
+
+    /// The API answered with a non-success HTTP status.
+    Status {
+        /// HTTP status code as defined in [RFC 9110](https://httpwg.org/specs/rfc9110.html#overview.of.status.codes).
+        status_code: u16,
+
+        /// Message from the API's response envelope, if the body had one.
+        message: Option<String>,
+    },
+
+    /// The response body could not be decoded into the expected structure.
+    Decode {
+        /// HTTP method of the request
+        method: Method,
+
+        /// Endpoint path, relative to the API base URL
+        path: String,
+
+        /// Underlying JSON error
+        source: serde_json::Error,
+
+        /// Raw response body
+        body: String,
+    },
+
+    /// The request body could not be encoded as JSON.
+    Encode(serde_json::Error),
+
+    /// An authenticated endpoint was called without an API key.
+    MissingApiKey {
+        /// HTTP method of the request
+        method: Method,
+
+        /// Endpoint path, relative to the API base URL
+        path: String,
+    },
+
+    /// The input was rejected before any request was sent.
+    Validation(String),
+
+    /// A local file could not be read or written.
+    Io(std::io::Error),
+}
+
+impl OmglolError {
+    /// Build a `Status` error, taking the message from the body if it is an
+    /// API response envelope.
+    pub(crate) fn from_status(status_code: u16, body: &str) -> OmglolError {
+        let message = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|value| {
+                value["response"]["message"]
+                    .as_str()
+                    .map(|message| message.to_string())
+            });
+
+        OmglolError::Status {
+            status_code,
+            message,
+        }
+    }
+
+    /// Beginning of the raw response body of a `Decode` error, cut at
+    /// 200 characters.
+    pub fn body_snippet(&self) -> Option<&str> {
+        match self {
+            OmglolError::Decode { body, .. } => Some(snippet(body)),
+            _ => None,
+        }
+    }
+
+    /// Drop the request URL from a `Transport` error, for requests whose
+    /// query holds secrets.
+    pub(crate) fn without_url(self) -> OmglolError {
+        match self {
+            OmglolError::Transport(error) => OmglolError::Transport(error.without_url()),
+            error => error,
+        }
+    }
+
+    /// HTTP status code returned by the API, if the error came from one.
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            OmglolError::Status { status_code, .. } => Some(*status_code),
+            OmglolError::Transport(error) => error.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OmglolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OmglolError::Transport(error) => write!(f, "Transport error: {}", error),
+            OmglolError::Status {
+                status_code,
+                message: Some(message),
+            } => write!(
+                f,
+                "Request error. HTTP status code: {}. {}",
+                status_code, message
+            ),
+            OmglolError::Status {
+                status_code,
+                message: None,
+            } => write!(f, "Request error. HTTP status code: {}.", status_code),
+            OmglolError::Decode {
+                method,
+                path,
+                source,
+                body,
+            } => write!(
+                f,
+                "Could not decode API response to {} {}: {}. Body: {:?}",
+                method,
+                path,
+                source,
+                snippet(body)
+            ),
+            OmglolError::Encode(error) => write!(f, "Could not encode request body: {}", error),
+            OmglolError::MissingApiKey { method, path } => write!(
+                f,
+                "No API key set for authenticated request {} {}.",
+                method, path
+            ),
+            OmglolError::Validation(reason) => write!(f, "Invalid input: {}", reason),
+            OmglolError::Io(error) => write!(f, "I/O error: {}", error),
+        }
+    }
+}
+
+impl Error for OmglolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OmglolError::Transport(error) => Some(error),
+            OmglolError::Decode { source, .. } => Some(source),
+            OmglolError::Encode(error) => Some(error),
+            OmglolError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for OmglolError {
+    fn from(error: reqwest::Error) -> OmglolError {
+        OmglolError::Transport(error)
+    }
+}
+
+impl From<std::io::Error> for OmglolError {
+    fn from(error: std::io::Error) -> OmglolError {
+        OmglolError::Io(error)
+    }
+}
+
+/// Serializing a request body is the only place a bare `serde_json::Error`
+/// is converted; decoding errors always carry the raw body.
+impl From<serde_json::Error> for OmglolError {
+    fn from(error: serde_json::Error) -> OmglolError {
+        OmglolError::Encode(error)
+    }
+}
+
+/// First `BODY_SNIPPET_LENGTH` characters of `body`.
+fn snippet(body: &str) -> &str {
+    match body.char_indices().nth(BODY_SNIPPET_LENGTH) {
+        Some((end, _)) => &body[..end],
+        None => body,
+    }
+}
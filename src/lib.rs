@@ -14,10 +14,24 @@ This Source Code Form is subject to the terms of the Mozilla Public License, v.
 #![doc = include_str!("../README.md")]
 
 //pub mod requests;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
+pub mod credentials;
+pub mod dns;
 pub mod email;
+pub mod error;
+pub mod oauth;
+pub mod pastebin;
+pub mod rate_limit;
+pub mod retry;
+pub mod statuslog;
+#[cfg(feature = "tracing")]
+mod telemetry;
+pub mod transport;
 
 #[allow(dead_code)]
 pub mod structures;
 
 pub use client::OmglolClient;
+pub use error::OmglolError;
@@ -0,0 +1,173 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+OAuth - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! OAuth authorization-code flow, to act on behalf of other omg.lol members.
+//!
+//! Register an OAuth application on [home.omg.lol](https://home.omg.lol),
+//! send the member to [`OAuthClient::authorize_url`], then exchange the code
+//! omg.lol redirects back with:
+//!
+//! ```rust,no_run
+//! use omglol::{oauth::OAuthClient, OmglolClient};
+//!
+//! # async fn run() -> Result<(), omglol::OmglolError> {
+//! let oauth = OAuthClient::new(
+//!     &OmglolClient::new(),
+//!     "YOUR_CLIENT_ID",
+//!     "YOUR_CLIENT_SECRET",
+//!     "https://example.com/callback",
+//! );
+//!
+//! // Redirect the member here, with a random state kept in their session.
+//! let url = oauth.authorize_url("random-state")?;
+//!
+//! // On the callback, check the state and exchange the code.
+//! let client = oauth.authenticate("code-from-the-callback").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use reqwest::Method;
+use url::{form_urlencoded, Url};
+
+use crate::client::{Auth, NoAuth, OmglolClient, Route};
+use crate::error::OmglolError;
+use crate::structures::{RequestResponse, Token};
+
+/// Page where members grant access to an application
+pub const DEFAULT_AUTHORIZE_URL: &str = "https://home.omg.lol/oauth/authorize";
+
+/// Scope requested unless another one is set, the only one omg.lol offers
+pub const DEFAULT_SCOPE: &str = "everything";
+
+/// An omg.lol OAuth application
+#[derive(Clone)]
+pub struct OAuthClient {
+    client: OmglolClient<NoAuth>,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+    scope: String,
+    authorize_endpoint: String,
+}
+
+impl OAuthClient {
+    /// Create a new `OAuthClient`.
+    ///
+    /// The token exchange is sent through `client`, so it honours its base URL.
+    ///
+    /// Arguments:
+    /// * `client`, the client used to exchange codes for tokens
+    /// * `client_id` and `client_secret`, the application's credentials
+    /// * `redirect_uri`, where omg.lol sends members back to
+    pub fn new(
+        client: &OmglolClient<NoAuth>,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+        redirect_uri: impl Into<String>,
+    ) -> OAuthClient {
+        OAuthClient {
+            client: client.clone(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            redirect_uri: redirect_uri.into(),
+            scope: DEFAULT_SCOPE.to_string(),
+            authorize_endpoint: DEFAULT_AUTHORIZE_URL.to_string(),
+        }
+    }
+
+    /// Request `scope` instead of [`DEFAULT_SCOPE`].
+    pub fn scope(mut self, scope: impl Into<String>) -> OAuthClient {
+        self.scope = scope.into();
+        self
+    }
+
+    /// Send members to `authorize_endpoint` instead of [`DEFAULT_AUTHORIZE_URL`].
+    pub fn authorize_endpoint(mut self, authorize_endpoint: impl Into<String>) -> OAuthClient {
+        self.authorize_endpoint = authorize_endpoint.into();
+        self
+    }
+
+    /// URL to send a member to so they can grant access.
+    ///
+    /// `state` is returned untouched on the callback; use a random value tied
+    /// to the member's session to protect against CSRF.
+    pub fn authorize_url(&self, state: &str) -> Result<Url, OmglolError> {
+        Url::parse_with_params(
+            &self.authorize_endpoint,
+            &[
+                ("client_id", self.client_id.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("scope", self.scope.as_str()),
+                ("response_type", "code"),
+                ("state", state),
+            ],
+        )
+        .map_err(|error| {
+            OmglolError::Validation(format!(
+                "invalid authorize endpoint {:?}: {}",
+                self.authorize_endpoint, error
+            ))
+        })
+    }
+
+    /// Exchange the `code` from the callback for an access token.
+    pub async fn exchange_code(&self, code: &str) -> Result<Token, OmglolError> {
+        if code.trim().is_empty() {
+            return Err(OmglolError::Validation(
+                "authorization code is empty".to_string(),
+            ));
+        }
+
+        let query = form_urlencoded::Serializer::new(String::new())
+            .append_pair("client_id", &self.client_id)
+            .append_pair("client_secret", &self.client_secret)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("code", code)
+            .append_pair("scope", &self.scope)
+            .finish();
+        // The code is single-use, so a retry could only fail.
+        let route = Route::new("oauth/", "oauth/".to_string())
+            .query(query)
+            .single_attempt();
+
+        let body = self
+            .client
+            .send_raw(false, Method::GET, &route, None)
+            .await
+            .map_err(OmglolError::without_url)?
+            .body;
+
+        // The token is sent on its own, but accept it inside the usual
+        // response envelope too.
+        serde_json::from_str::<Token>(&body)
+            .or_else(|error| {
+                serde_json::from_str::<RequestResponse<Token>>(&body)
+                    .map(|envelope| envelope.response)
+                    .map_err(|_| error)
+            })
+            .map_err(|source| OmglolError::Decode {
+                method: Method::GET,
+                path: "oauth/".to_string(),
+                source,
+                body,
+            })
+    }
+
+    /// Exchange the `code` from the callback for a client acting on behalf
+    /// of the member.
+    pub async fn authenticate(&self, code: &str) -> Result<OmglolClient<Auth>, OmglolError> {
+        let token = self.exchange_code(code).await?;
+        Ok(self.client.auth_with_token(&token))
+    }
+}
@@ -0,0 +1,16 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Pastebin - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Helpers to manage an address' pastebin
+
+pub mod sync;
@@ -0,0 +1,456 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Pastebin sync - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Mirror a local directory to an address' pastebin.
+//!
+//! Every regular file of the directory is a paste titled after the file
+//! name. Hidden files and pastes whose title starts with a dot are left out
+//! of the mirror in both directions. Push the directory to
+//! [paste.lol](https://paste.lol), or pull the pastebin into the directory,
+//! sending only what changed:
+//!
+//! ```rust,no_run
+//! use omglol::{pastebin::sync::{self, Direction, SyncOptions}, OmglolClient};
+//!
+//! # async fn run() -> Result<(), omglol::OmglolError> {
+//! let client = OmglolClient::new().auth("YOUR_API_KEY".to_string());
+//! let options = SyncOptions::new().direction(Direction::Push).prune(true);
+//!
+//! let plan = sync::plan(&client, "foobar", "snippets", &options).await?;
+//! println!("{}", plan); // dry run
+//!
+//! let report = plan.apply(&client).await;
+//! assert!(report.is_success());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::client::{Auth, OmglolClient};
+use crate::error::OmglolError;
+use crate::structures::Paste;
+
+/// Which side of the sync is the source of truth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// Upload local files to the pastebin.
+    #[default]
+    Push,
+
+    /// Download pastes to the local directory.
+    Pull,
+}
+
+/// Settings of a pastebin sync
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    pub(crate) direction: Direction,
+    pub(crate) prune: bool,
+    pub(crate) overwrite: bool,
+    pub(crate) listed: bool,
+}
+
+impl SyncOptions {
+    /// Push without pruning or overwriting newer pastes; new pastes are
+    /// unlisted.
+    pub fn new() -> SyncOptions {
+        SyncOptions::default()
+    }
+
+    /// Set whether the directory is pushed to or pulled from the pastebin.
+    pub fn direction(mut self, direction: Direction) -> SyncOptions {
+        self.direction = direction;
+        self
+    }
+
+    /// Delete pastes (or files, when pulling) missing from the source.
+    pub fn prune(mut self, prune: bool) -> SyncOptions {
+        self.prune = prune;
+        self
+    }
+
+    /// Replace copies that were modified after the source instead of
+    /// reporting them as conflicts.
+    pub fn overwrite(mut self, overwrite: bool) -> SyncOptions {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Whether pastes created by a push are listed. Updated pastes keep
+    /// their current visibility.
+    pub fn listed(mut self, listed: bool) -> SyncOptions {
+        self.listed = listed;
+        self
+    }
+}
+
+/// A single step of a `SyncPlan`
+///
+/// `current` is the copy being replaced and `desired` the one from the
+/// source: the local file when pushing, the paste when pulling.
+#[derive(Debug, Clone)]
+pub enum SyncAction {
+    /// The paste or file is missing from the target and will be created.
+    Create(Paste),
+
+    /// Both copies exist with different content.
+    Update { current: Paste, desired: Paste },
+
+    /// The copy is missing from the source and will be deleted.
+    Delete(Paste),
+
+    /// Both copies have the same content.
+    Unchanged(Paste),
+
+    /// The contents differ but the target was modified after the source, so
+    /// it is left alone unless `SyncOptions::overwrite` is set.
+    Conflict { current: Paste, desired: Paste },
+}
+
+impl SyncAction {
+    /// `true` for actions `SyncPlan::apply` carries out.
+    pub fn is_change(&self) -> bool {
+        matches!(
+            self,
+            SyncAction::Create(_) | SyncAction::Update { .. } | SyncAction::Delete(_)
+        )
+    }
+
+    /// Title of the paste the action is about.
+    pub fn title(&self) -> &str {
+        match self {
+            SyncAction::Create(paste)
+            | SyncAction::Delete(paste)
+            | SyncAction::Unchanged(paste)
+            | SyncAction::Update { desired: paste, .. }
+            | SyncAction::Conflict { desired: paste, .. } => &paste.title,
+        }
+    }
+}
+
+/// Changes needed to mirror a directory and a pastebin
+#[derive(Debug, Clone)]
+pub struct SyncPlan {
+    /// Address the plan applies to
+    pub address: String,
+
+    /// Local directory the plan applies to
+    pub directory: PathBuf,
+
+    /// Whether the plan uploads or downloads
+    pub direction: Direction,
+
+    /// Planned actions, sorted by title
+    pub actions: Vec<SyncAction>,
+}
+
+impl SyncPlan {
+    /// Compute the plan between the `local` files and the `remote` pastes,
+    /// without any requests.
+    ///
+    /// Copies are matched by title. Matching copies with the same content are
+    /// `Unchanged`. Otherwise the target is updated, unless its
+    /// `modified_on` is later than the source's (a `Conflict`). Target copies
+    /// without a source are only deleted when pruning. Titles starting with a
+    /// dot are ignored on both sides.
+    pub fn new(
+        address: &str,
+        directory: impl Into<PathBuf>,
+        local: &[Paste],
+        remote: &[Paste],
+        options: &SyncOptions,
+    ) -> SyncPlan {
+        let (source, target) = match options.direction {
+            Direction::Push => (local, remote),
+            Direction::Pull => (remote, local),
+        };
+
+        let mut remaining: Vec<Option<&Paste>> = target
+            .iter()
+            .filter(|paste| !is_hidden(&paste.title))
+            .map(Some)
+            .collect();
+        let mut actions = Vec::new();
+
+        for wanted in source.iter().filter(|paste| !is_hidden(&paste.title)) {
+            let slot = remaining
+                .iter_mut()
+                .find(|slot| slot.is_some_and(|paste| paste.title == wanted.title));
+
+            actions.push(match slot.and_then(|slot| slot.take()) {
+                None => SyncAction::Create(Paste {
+                    listed: options.listed,
+                    ..wanted.clone()
+                }),
+                Some(current) if current.content == wanted.content => {
+                    SyncAction::Unchanged(current.clone())
+                }
+                Some(current) => {
+                    let desired = match options.direction {
+                        Direction::Push => Paste {
+                            listed: current.listed,
+                            ..wanted.clone()
+                        },
+                        Direction::Pull => wanted.clone(),
+                    };
+
+                    if !options.overwrite && is_newer(current, wanted) {
+                        SyncAction::Conflict {
+                            current: current.clone(),
+                            desired,
+                        }
+                    } else {
+                        SyncAction::Update {
+                            current: current.clone(),
+                            desired,
+                        }
+                    }
+                }
+            });
+        }
+
+        if options.prune {
+            actions.extend(
+                remaining
+                    .into_iter()
+                    .flatten()
+                    .map(|paste| SyncAction::Delete(paste.clone())),
+            );
+        }
+
+        actions.sort_by(|a, b| a.title().cmp(b.title()));
+
+        SyncPlan {
+            address: address.to_string(),
+            directory: directory.into(),
+            direction: options.direction,
+            actions,
+        }
+    }
+
+    /// `true` if applying the plan would change the target.
+    pub fn has_changes(&self) -> bool {
+        self.actions.iter().any(SyncAction::is_change)
+    }
+
+    /// Actions skipped because the target was modified after the source.
+    pub fn conflicts(&self) -> impl Iterator<Item = &SyncAction> {
+        self.actions
+            .iter()
+            .filter(|action| matches!(action, SyncAction::Conflict { .. }))
+    }
+
+    /// Apply the plan, continuing past failed pastes.
+    ///
+    /// Pushing uploads and deletes pastes of `address`; pulling writes and
+    /// removes files in `directory`, creating it if needed.
+    pub async fn apply(&self, client: &OmglolClient<Auth>) -> SyncReport {
+        let mut results = Vec::new();
+
+        for action in self.actions.iter().filter(|action| action.is_change()) {
+            let result = match self.direction {
+                Direction::Push => self.push(client, action).await,
+                Direction::Pull => self.pull(action),
+            };
+
+            results.push(SyncResult {
+                action: action.clone(),
+                result,
+            });
+        }
+
+        SyncReport { results }
+    }
+
+    async fn push(
+        &self,
+        client: &OmglolClient<Auth>,
+        action: &SyncAction,
+    ) -> Result<(), OmglolError> {
+        match action {
+            SyncAction::Create(desired) | SyncAction::Update { desired, .. } => client
+                .upload_paste(&self.address, desired)
+                .await
+                .map(|_| ()),
+            SyncAction::Delete(current) => client
+                .delete_paste(&self.address, &current.title)
+                .await
+                .map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+
+    fn pull(&self, action: &SyncAction) -> Result<(), OmglolError> {
+        match action {
+            SyncAction::Create(desired) | SyncAction::Update { desired, .. } => {
+                let path = file_path(&self.directory, &desired.title)?;
+                fs::create_dir_all(&self.directory)?;
+                fs::write(path, &desired.content)?;
+            }
+            SyncAction::Delete(current) => {
+                fs::remove_file(file_path(&self.directory, &current.title)?)?
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Dry-run output, one line per action: `+` create, `~` update,
+/// `-` delete, `=` unchanged, `!` conflict.
+impl fmt::Display for SyncPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (direction, newer) = match self.direction {
+            Direction::Push => ("push from", "newer on paste.lol"),
+            Direction::Pull => ("pull into", "newer locally"),
+        };
+        writeln!(
+            f,
+            "Pastebin plan for {} ({} {}):",
+            self.address,
+            direction,
+            self.directory.display()
+        )?;
+
+        for action in &self.actions {
+            let title = action.title();
+            match action {
+                SyncAction::Create(_) => writeln!(f, "  + {}", title)?,
+                SyncAction::Update { .. } => writeln!(f, "  ~ {}", title)?,
+                SyncAction::Delete(_) => writeln!(f, "  - {}", title)?,
+                SyncAction::Unchanged(_) => writeln!(f, "  = {}", title)?,
+                SyncAction::Conflict { .. } => writeln!(f, "  ! {} ({})", title, newer)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome of applying one action of a `SyncPlan`
+#[derive(Debug)]
+pub struct SyncResult {
+    pub action: SyncAction,
+    pub result: Result<(), OmglolError>,
+}
+
+/// Per-paste outcome of `SyncPlan::apply`
+#[derive(Debug)]
+pub struct SyncReport {
+    /// One entry per applied change, in plan order
+    pub results: Vec<SyncResult>,
+}
+
+impl SyncReport {
+    /// `true` if every change was applied.
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|result| result.result.is_ok())
+    }
+
+    /// Changes that could not be applied.
+    pub fn failures(&self) -> impl Iterator<Item = &SyncResult> {
+        self.results.iter().filter(|result| result.result.is_err())
+    }
+}
+
+/// Read the regular files of `directory` as pastes, skipping hidden files.
+///
+/// Titles are the file names and `modified_on` the modification time of the
+/// file. Subdirectories are ignored, and files whose name or content is not
+/// UTF-8 are a `Validation` error.
+pub fn read_directory(directory: impl AsRef<Path>) -> Result<Vec<Paste>, OmglolError> {
+    let mut pastes = Vec::new();
+
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+
+        let title = entry.file_name().into_string().map_err(|name| {
+            OmglolError::Validation(format!("{:?} is not a valid paste title", name))
+        })?;
+        if is_hidden(&title) {
+            continue;
+        }
+
+        let content = String::from_utf8(fs::read(entry.path())?)
+            .map_err(|_| OmglolError::Validation(format!("{} is not a UTF-8 file", title)))?;
+        let modified_on = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|modified| modified.as_secs() as i64);
+
+        pastes.push(Paste {
+            modified_on,
+            ..Paste::new(title, content)
+        });
+    }
+
+    pastes.sort_by(|a, b| a.title.cmp(&b.title));
+    Ok(pastes)
+}
+
+/// Read `directory`, fetch the pastebin of `address` and compute the plan.
+///
+/// When pulling, a missing directory counts as empty.
+pub async fn plan(
+    client: &OmglolClient<Auth>,
+    address: &str,
+    directory: impl AsRef<Path>,
+    options: &SyncOptions,
+) -> Result<SyncPlan, OmglolError> {
+    let directory = directory.as_ref();
+    let local = match read_directory(directory) {
+        Err(OmglolError::Io(error))
+            if options.direction == Direction::Pull && error.kind() == io::ErrorKind::NotFound =>
+        {
+            Vec::new()
+        }
+        local => local?,
+    };
+    let remote = client.get_pastebin(address).await?.response.pastebin;
+
+    Ok(SyncPlan::new(address, directory, &local, &remote, options))
+}
+
+/// Dot-files are not read from the directory, so the pastes they would be
+/// written to are not synced either.
+fn is_hidden(title: &str) -> bool {
+    title.starts_with('.')
+}
+
+fn is_newer(paste: &Paste, than: &Paste) -> bool {
+    matches!((paste.modified_on, than.modified_on), (Some(a), Some(b)) if a > b)
+}
+
+/// Path of the file for `title`, refusing titles that would escape
+/// `directory`.
+fn file_path(directory: &Path, title: &str) -> Result<PathBuf, OmglolError> {
+    if title.is_empty() || title == "." || title == ".." || title.contains(['/', '\\']) {
+        return Err(OmglolError::Validation(format!(
+            "{:?} cannot be used as a file name",
+            title
+        )));
+    }
+
+    Ok(directory.join(title))
+}
@@ -0,0 +1,194 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Rate limit - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Client-side rate limiting with token buckets.
+//!
+//! A `RateLimiter` set on [`OmglolClientBuilder`](crate::client::OmglolClientBuilder)
+//! is shared by every clone of the client. Requests wait for a token instead
+//! of failing when a bucket is empty.
+//!
+//! ```rust
+//! use omglol::{rate_limit::{Budget, RateLimiter}, OmglolClient};
+//!
+//! let client = OmglolClient::builder()
+//!     .rate_limiter(
+//!         RateLimiter::new(Budget::per_second(10))
+//!             .group("dns", Budget::per_minute(30)),
+//!     )
+//!     .build();
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures_timer::Delay;
+
+/// How many requests a bucket allows, and how fast it refills.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    requests: u32,
+    period: Duration,
+}
+
+impl Budget {
+    /// Allow `requests` requests per `period`, with bursts of up to
+    /// `requests` requests.
+    pub fn new(requests: u32, period: Duration) -> Budget {
+        Budget {
+            requests: requests.max(1),
+            period,
+        }
+    }
+
+    /// Allow `requests` requests per second.
+    pub fn per_second(requests: u32) -> Budget {
+        Budget::new(requests, Duration::from_secs(1))
+    }
+
+    /// Allow `requests` requests per minute.
+    pub fn per_minute(requests: u32) -> Budget {
+        Budget::new(requests, Duration::from_secs(60))
+    }
+}
+
+/// Token buckets shared by every clone of a client.
+///
+/// Every request takes a token from the default bucket, and from the bucket
+/// of its route group if that group has its own budget.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    default: Bucket,
+    groups: HashMap<String, Bucket>,
+}
+
+impl RateLimiter {
+    /// Create a `RateLimiter` applying `budget` to every request.
+    pub fn new(budget: Budget) -> RateLimiter {
+        RateLimiter {
+            buckets: Arc::new(Mutex::new(Buckets {
+                default: Bucket::new(budget),
+                groups: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Give the route `group` its own budget, on top of the default one.
+    ///
+    /// See [`route_group`] for how requests are grouped.
+    pub fn group(self, group: impl Into<String>, budget: Budget) -> RateLimiter {
+        self.buckets
+            .lock()
+            .unwrap()
+            .groups
+            .insert(group.into(), Bucket::new(budget));
+        self
+    }
+
+    /// Wait until a request to `path` is allowed, then take its tokens.
+    pub(crate) async fn acquire(&self, path: &str) {
+        let group = route_group(path);
+
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap();
+                let Buckets { default, groups } = &mut *buckets;
+                let now = Instant::now();
+
+                let mut group_bucket = groups.get_mut(group);
+                let wait = default.wait(now).max(
+                    group_bucket
+                        .as_mut()
+                        .map_or(Duration::ZERO, |bucket| bucket.wait(now)),
+                );
+
+                if wait.is_zero() {
+                    default.take();
+                    if let Some(bucket) = group_bucket {
+                        bucket.take();
+                    }
+                }
+                wait
+            };
+
+            if wait.is_zero() {
+                return;
+            }
+            Delay::new(wait).await;
+        }
+    }
+}
+
+/// Route group of an endpoint path, used to pick a per-group budget.
+///
+/// Paths under `address/<address>/` are grouped by the resource that
+/// follows (e.g. `dns`, `statuses`, `pastebin`), every other path by its
+/// first segment (e.g. `account`, `statuslog`, `service`).
+pub fn route_group(path: &str) -> &str {
+    let path = path.split('?').next().unwrap_or_default();
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+
+    match segments.next() {
+        Some("address") => segments.nth(1).unwrap_or("address"),
+        Some(first) => first,
+        None => "",
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    per_token: Duration,
+    refilled: Instant,
+}
+
+impl Bucket {
+    fn new(budget: Budget) -> Bucket {
+        Bucket {
+            capacity: f64::from(budget.requests),
+            tokens: f64::from(budget.requests),
+            per_token: budget.period / budget.requests,
+            refilled: Instant::now(),
+        }
+    }
+
+    /// Refill the bucket and return how long until a token is available.
+    fn wait(&mut self, now: Instant) -> Duration {
+        if !self.per_token.is_zero() {
+            let elapsed = now.duration_since(self.refilled);
+            self.tokens = (self.tokens + elapsed.as_secs_f64() / self.per_token.as_secs_f64())
+                .min(self.capacity);
+        } else {
+            self.tokens = self.capacity;
+        }
+        self.refilled = now;
+
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            self.per_token.mul_f64(1.0 - self.tokens)
+        }
+    }
+
+    fn take(&mut self) {
+        self.tokens -= 1.0;
+    }
+}
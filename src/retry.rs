@@ -0,0 +1,211 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Retry - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Retrying failed requests with exponential backoff.
+//!
+//! ```rust
+//! use omglol::{retry::RetryPolicy, OmglolClient};
+//! use std::time::Duration;
+//!
+//! let client = OmglolClient::builder()
+//!     .retry_policy(
+//!         RetryPolicy::new()
+//!             .max_attempts(5)
+//!             .initial_backoff(Duration::from_millis(250)),
+//!     )
+//!     .build();
+//! ```
+
+use std::{
+    fmt, io,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use reqwest::{header::HeaderMap, Method};
+
+use crate::error::OmglolError;
+
+type RetryHook = Arc<dyn Fn(&Method, &str) -> bool + Send + Sync>;
+
+/// When and how often `OmglolClient` retries a failed request.
+///
+/// A request is retried when the API answers `429 Too Many Requests` or a
+/// `5xx` status, or when the connection fails or times out. Only idempotent
+/// methods (`GET`, `HEAD`, `PUT`, `DELETE`, `OPTIONS`, `TRACE`) are retried unless
+/// other requests are opted in with [`retry_non_idempotent`](Self::retry_non_idempotent)
+/// or [`retry_when`](Self::retry_when).
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retry_non_idempotent: bool,
+    retry_when: Option<RetryHook>,
+}
+
+impl RetryPolicy {
+    /// Create a `RetryPolicy` making up to 3 attempts, waiting 500ms then
+    /// doubling up to 30 seconds, with jitter.
+    pub fn new() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retry_non_idempotent: false,
+            retry_when: None,
+        }
+    }
+
+    /// A policy that never retries, the client's default.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::new().max_attempts(1)
+    }
+
+    /// Total number of attempts, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> RetryPolicy {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Wait before the first retry. Every following wait doubles.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> RetryPolicy {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Longest wait between two attempts, also applied to `Retry-After`.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> RetryPolicy {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Randomise each wait between half and all of its length, so clients
+    /// failing together do not retry together.
+    pub fn jitter(mut self, jitter: bool) -> RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Also retry `POST` and `PATCH` requests.
+    pub fn retry_non_idempotent(mut self, retry_non_idempotent: bool) -> RetryPolicy {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// Decide per request whether it may be retried, e.g. to opt in a single
+    /// `POST` endpoint. Called with the method and the endpoint path; takes
+    /// precedence over the idempotency rule.
+    pub fn retry_when<F>(mut self, hook: F) -> RetryPolicy
+    where
+        F: Fn(&Method, &str) -> bool + Send + Sync + 'static,
+    {
+        self.retry_when = Some(Arc::new(hook));
+        self
+    }
+
+    /// Whether a request may be retried at all.
+    pub(crate) fn allows(&self, method: &Method, path: &str) -> bool {
+        if self.max_attempts <= 1 {
+            return false;
+        }
+
+        match &self.retry_when {
+            Some(hook) => hook(method, path),
+            None => self.retry_non_idempotent || is_idempotent(method),
+        }
+    }
+
+    /// Whether attempt number `attempt` may be followed by another one.
+    pub(crate) fn has_attempts_left(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// Wait after failed attempt number `attempt` (starting at 1).
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let delay = retry_after.unwrap_or_else(|| {
+            let factor = 2u32.saturating_pow(attempt.saturating_sub(1).min(16));
+            self.initial_backoff.saturating_mul(factor)
+        });
+        let delay = delay.min(self.max_backoff);
+
+        if self.jitter && retry_after.is_none() {
+            let half = delay / 2;
+            half + half.mul_f64(fastrand::f64())
+        } else {
+            delay
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy::new()
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("jitter", &self.jitter)
+            .field("retry_non_idempotent", &self.retry_non_idempotent)
+            .field("retry_when", &self.retry_when.is_some())
+            .finish()
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
+
+/// Whether `error` is worth another attempt.
+pub(crate) fn is_retryable(error: &OmglolError) -> bool {
+    match error {
+        OmglolError::Status { status_code, .. } => {
+            *status_code == 429 || (500..600).contains(status_code)
+        }
+        OmglolError::Transport(error) => error.is_connect() || error.is_timeout(),
+        OmglolError::Io(error) => matches!(
+            error.kind(),
+            io::ErrorKind::ConnectionRefused
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::TimedOut
+        ),
+        _ => false,
+    }
+}
+
+/// Parse a `Retry-After` header, either in seconds or as an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => httpdate::parse_http_date(value)
+            .ok()
+            .map(|date| date.duration_since(SystemTime::now()).unwrap_or_default()),
+    }
+}
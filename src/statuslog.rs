@@ -0,0 +1,320 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Statuslog - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Follow new statuses as they are posted.
+//!
+//! ```rust,no_run
+//! use futures::StreamExt;
+//! use omglol::{statuslog::StatusPoller, OmglolClient};
+//! use std::time::Duration;
+//!
+//! # async fn run() {
+//! let client = OmglolClient::new();
+//! let mut statuses = Box::pin(
+//!     StatusPoller::address(&client, "foobar")
+//!         .interval(Duration::from_secs(60))
+//!         .skip_existing(true)
+//!         .into_stream(),
+//! );
+//!
+//! while let Some(status) = statuses.next().await {
+//!     match status {
+//!         Ok(status) => println!("{} {}", status.emoji, status.content),
+//!         Err(error) => eprintln!("{}", error),
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::{
+    collections::{HashSet, VecDeque},
+    fs, io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::{stream, Stream};
+use futures_timer::Delay;
+
+use crate::client::{NoAuth, OmglolClient};
+use crate::error::OmglolError;
+use crate::structures::Status;
+
+/// Where a `StatusPoller` reads statuses from
+#[derive(Debug, Clone)]
+pub enum StatusSource {
+    /// Statuses of a single address
+    Address(String),
+
+    /// Statuses of every address on the statuslog
+    Statuslog,
+}
+
+/// Persists the ID of the last status a `StatusPoller` yielded, so a
+/// restarted poller resumes where it left off.
+pub trait CursorStore: Send + Sync {
+    /// Load the last seen status ID, if any.
+    fn load(&self) -> Result<Option<String>, OmglolError>;
+
+    /// Store the last seen status ID.
+    fn save(&self, id: &str) -> Result<(), OmglolError>;
+}
+
+/// In-memory cursor, lost when the process exits.
+///
+/// Clones share the same cursor.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCursor {
+    id: Arc<Mutex<Option<String>>>,
+}
+
+impl MemoryCursor {
+    /// Create an empty `MemoryCursor`.
+    pub fn new() -> MemoryCursor {
+        MemoryCursor::default()
+    }
+
+    /// The last seen status ID.
+    pub fn get(&self) -> Option<String> {
+        self.id.lock().unwrap().clone()
+    }
+}
+
+impl CursorStore for MemoryCursor {
+    fn load(&self) -> Result<Option<String>, OmglolError> {
+        Ok(self.get())
+    }
+
+    fn save(&self, id: &str) -> Result<(), OmglolError> {
+        *self.id.lock().unwrap() = Some(id.to_string());
+        Ok(())
+    }
+}
+
+/// Cursor kept in a text file containing the last seen status ID.
+#[derive(Debug, Clone)]
+pub struct FileCursor {
+    path: PathBuf,
+}
+
+impl FileCursor {
+    /// Create a `FileCursor` stored at `path`. The file is created on the
+    /// first save.
+    pub fn new(path: impl Into<PathBuf>) -> FileCursor {
+        FileCursor { path: path.into() }
+    }
+}
+
+impl CursorStore for FileCursor {
+    fn load(&self) -> Result<Option<String>, OmglolError> {
+        match fs::read_to_string(&self.path) {
+            Ok(id) if id.trim().is_empty() => Ok(None),
+            Ok(id) => Ok(Some(id.trim().to_string())),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn save(&self, id: &str) -> Result<(), OmglolError> {
+        Ok(fs::write(&self.path, id)?)
+    }
+}
+
+/// Polls the statuslog and yields each new status once, oldest first.
+///
+/// New statuses are the ones listed above the newest status of the previous
+/// poll, so older statuses moving into view after a deletion are not
+/// mistaken for new ones.
+#[derive(Clone)]
+pub struct StatusPoller {
+    client: OmglolClient<NoAuth>,
+    source: StatusSource,
+    interval: Duration,
+    max_backoff: Duration,
+    skip_existing: bool,
+    cursor: Arc<dyn CursorStore>,
+}
+
+impl StatusPoller {
+    /// Create a `StatusPoller` for `source`.
+    ///
+    /// Defaults: poll every 60 seconds, back off up to 15 minutes on errors,
+    /// yield the statuses already posted and keep the cursor in memory.
+    pub fn new(client: &OmglolClient<NoAuth>, source: StatusSource) -> StatusPoller {
+        StatusPoller {
+            client: client.clone(),
+            source,
+            interval: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(15 * 60),
+            skip_existing: false,
+            cursor: Arc::new(MemoryCursor::new()),
+        }
+    }
+
+    /// Create a `StatusPoller` for the statuses of `address`.
+    pub fn address(client: &OmglolClient<NoAuth>, address: &str) -> StatusPoller {
+        StatusPoller::new(client, StatusSource::Address(address.to_string()))
+    }
+
+    /// Create a `StatusPoller` for the whole statuslog.
+    pub fn statuslog(client: &OmglolClient<NoAuth>) -> StatusPoller {
+        StatusPoller::new(client, StatusSource::Statuslog)
+    }
+
+    /// Time to wait between two polls.
+    pub fn interval(mut self, interval: Duration) -> StatusPoller {
+        self.interval = interval;
+        self
+    }
+
+    /// Longest wait after consecutive errors. The wait doubles with every
+    /// failed poll, starting from the interval.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> StatusPoller {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Only yield statuses posted after the first poll when the cursor is
+    /// empty, instead of every status the API returns.
+    ///
+    /// A stored cursor whose status is no longer listed, e.g. because it was
+    /// deleted, is treated like an empty cursor with this option set.
+    pub fn skip_existing(mut self, skip_existing: bool) -> StatusPoller {
+        self.skip_existing = skip_existing;
+        self
+    }
+
+    /// Persist the last seen status ID with `cursor`.
+    pub fn cursor_store(mut self, cursor: impl CursorStore + 'static) -> StatusPoller {
+        self.cursor = Arc::new(cursor);
+        self
+    }
+
+    /// Start polling.
+    ///
+    /// Errors are yielded as they happen; the stream keeps polling after
+    /// them, waiting longer after each consecutive failure.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Status, OmglolError>> {
+        let state = PollState {
+            poller: self,
+            pending: VecDeque::new(),
+            pending_error: None,
+            seen: None,
+            failures: 0,
+            polled: false,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            let item = state.next().await;
+            Some((item, state))
+        })
+    }
+
+    async fn fetch(&self) -> Result<Vec<Status>, OmglolError> {
+        Ok(match &self.source {
+            StatusSource::Address(address) => {
+                self.client
+                    .get_all_statuses(address)
+                    .await?
+                    .response
+                    .statuses
+            }
+            StatusSource::Statuslog => self.client.get_statuslog().await?.response.statuses,
+        })
+    }
+}
+
+struct PollState {
+    poller: StatusPoller,
+    pending: VecDeque<Status>,
+    pending_error: Option<OmglolError>,
+    /// IDs returned by the previous poll, `None` before the first one
+    seen: Option<HashSet<String>>,
+    failures: u32,
+    polled: bool,
+}
+
+impl PollState {
+    async fn next(&mut self) -> Result<Status, OmglolError> {
+        loop {
+            if let Some(error) = self.pending_error.take() {
+                return Err(error);
+            }
+
+            if let Some(status) = self.pending.pop_front() {
+                if let Err(error) = self.poller.cursor.save(&status.id) {
+                    self.pending_error = Some(error);
+                }
+                return Ok(status);
+            }
+
+            if self.polled {
+                Delay::new(self.delay()).await;
+            }
+            self.polled = true;
+
+            match self.poll().await {
+                Ok(()) => self.failures = 0,
+                Err(error) => {
+                    self.failures = self.failures.saturating_add(1);
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    fn delay(&self) -> Duration {
+        if self.failures == 0 {
+            return self.poller.interval;
+        }
+
+        let factor = 2u32.saturating_pow(self.failures.min(16));
+        self.poller
+            .interval
+            .saturating_mul(factor)
+            .min(self.poller.max_backoff)
+    }
+
+    /// Fetch statuses (newest first) and queue the new ones, oldest first.
+    async fn poll(&mut self) -> Result<(), OmglolError> {
+        let statuses = self.poller.fetch().await?;
+        let ids: HashSet<String> = statuses.iter().map(|status| status.id.clone()).collect();
+
+        let new: Vec<Status> = match self.seen.take() {
+            Some(seen) => statuses
+                .into_iter()
+                .take_while(|status| !seen.contains(&status.id))
+                .collect(),
+            None => match self.poller.cursor.load()? {
+                Some(cursor) if ids.contains(&cursor) => statuses
+                    .into_iter()
+                    .take_while(|status| status.id != cursor)
+                    .collect(),
+                None if !self.poller.skip_existing => statuses,
+                // The cursor's status is gone or existing statuses are
+                // skipped: start from the newest one.
+                _ => {
+                    if let Some(newest) = statuses.first() {
+                        self.poller.cursor.save(&newest.id)?;
+                    }
+                    Vec::new()
+                }
+            },
+        };
+
+        self.seen = Some(ids);
+        self.pending.extend(new.into_iter().rev());
+        Ok(())
+    }
+}
@@ -13,14 +13,96 @@ This Source Code Form is subject to the terms of the Mozilla Public License, v.
 
 //! Structures corresponding to various API elements
 
-use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, error::Error, fmt, fmt::Display};
+use crate::error::OmglolError;
+use email_address::EmailAddress;
+use reqwest::header::HeaderMap;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::HashMap,
+    fmt,
+    fmt::Display,
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+    time::Duration,
+};
+use url::Url;
 
 // fn return_true() -> bool {
 //     // This is the world's more useful function /s
 //     // #[serde(default)] needs a function to be supplied.
 //     true
 
+/// Deserialize a value the API sends either as a string or as a number.
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(serde_json::Number),
+    }
+
+    Ok(match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::String(string) => string,
+        StringOrNumber::Number(number) => number.to_string(),
+    })
+}
+
+/// Deserialize a Unix time the API sends either as a number or as a string.
+/// `null` and empty strings are `None`.
+fn optional_timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Timestamp {
+        Number(i64),
+        String(String),
+    }
+
+    match Option::<Timestamp>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Timestamp::Number(time)) => Ok(Some(time)),
+        Some(Timestamp::String(time)) if time.trim().is_empty() => Ok(None),
+        Some(Timestamp::String(time)) => time.trim().parse().map(Some).map_err(de::Error::custom),
+    }
+}
+
+/// Deserialize a flag the API sends as a boolean, a `0`/`1` number or a
+/// string. `null` and empty strings are `false`.
+pub(crate) fn flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Number(i64),
+        String(String),
+    }
+
+    Ok(match Option::<Flag>::deserialize(deserializer)? {
+        None => false,
+        Some(Flag::Bool(flag)) => flag,
+        Some(Flag::Number(number)) => number != 0,
+        Some(Flag::String(string)) => !matches!(
+            string.trim().to_ascii_lowercase().as_str(),
+            "" | "0" | "false" | "no" | "off"
+        ),
+    })
+}
+
+/// Deserialize a list of email addresses, skipping blank entries.
+fn destinations<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<EmailAddress>, D::Error> {
+    Option::<Vec<String>>::deserialize(deserializer)?
+        .unwrap_or_default()
+        .iter()
+        .map(|destination| destination.trim())
+        .filter(|destination| !destination.is_empty())
+        .map(|destination| EmailAddress::from_str(destination).map_err(de::Error::custom))
+        .collect()
+}
+
+/// Serialize a flag as `1` or `0`, as the API expects.
+fn flag_as_number<S: Serializer>(flag: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u8(u8::from(*flag))
+}
+
 /// Response to an account GET request
 #[derive(Deserialize, Debug)]
 pub struct AccountResponse {
@@ -125,7 +207,7 @@ pub struct DNSrecords {
 }
 
 /// DNS record
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct DNSrecord {
     /// Record ID
     pub id: i32,
@@ -134,6 +216,7 @@ pub struct DNSrecord {
     pub name: String,
 
     /// Entry data/target
+    #[serde(alias = "content")]
     pub data: String,
 
     /// Record priority, if applicable
@@ -155,22 +238,236 @@ pub struct DNSrecord {
     pub record_type: DNStype,
 }
 
-/// DNS record types supported by omg.lol DNS routing.
+/// Response to a DNS record creation or update request
+#[derive(Deserialize, Debug)]
+pub struct DNSrecordResponse {
+    /// Status message returned by the API
+    pub message: String,
+
+    /// Record as stored by the DNS provider
+    pub response_received: DNSrecordReceived,
+}
+
+impl DNSrecordResponse {
+    /// The created or updated DNS record
+    pub fn record(&self) -> &DNSrecord {
+        &self.response_received.data
+    }
+}
+
+/// Wrapper around the record returned by the DNS provider
 #[derive(Deserialize, Debug)]
+pub struct DNSrecordReceived {
+    pub data: DNSrecord,
+}
+
+/// DNS record to be created or updated.
+///
+/// Usage:
+/// ```rust
+/// use omglol::structures::{DNSrecordBuilder, DNStype};
+///
+/// let record = DNSrecordBuilder::new(DNStype::MX, "".to_string(), "mx.example.com".to_string())
+///     .priority(10)
+///     .ttl(3600);
+/// assert!(record.validate().is_ok());
+/// ```
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DNSrecordBuilder {
+    /// DNS record type
+    #[serde(rename = "type")]
+    pub record_type: DNStype,
+
+    /// Domain relative to your address, e.g. `www`
+    pub name: String,
+
+    /// Entry data/target
+    pub data: String,
+
+    /// Record priority, required for `MX` and `SRV` records
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+
+    /// Record TTL (time to live), the API default is used if `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<i32>,
+}
+
+impl DNSrecordBuilder {
+    /// Create a new `DNSrecordBuilder`.
+    ///
+    /// Arguments:
+    /// * `record_type`, the DNS record type
+    /// * `name`, the domain relative to your address
+    /// * `data`, the record's data/target
+    pub fn new(record_type: DNStype, name: String, data: String) -> DNSrecordBuilder {
+        DNSrecordBuilder {
+            record_type,
+            name,
+            data,
+            priority: None,
+            ttl: None,
+        }
+    }
+
+    /// Set the record priority.
+    pub fn priority(mut self, priority: i32) -> DNSrecordBuilder {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Set the record TTL, in seconds.
+    pub fn ttl(mut self, ttl: i32) -> DNSrecordBuilder {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Check the record against the rules of its `DNStype`.
+    ///
+    /// Returns `OmglolError::Validation` describing the first problem found.
+    pub fn validate(&self) -> Result<(), OmglolError> {
+        let invalid = |reason: String| Err(OmglolError::Validation(reason));
+
+        if self.name.chars().any(char::is_whitespace) {
+            return invalid(format!(
+                "DNS record name {:?} contains whitespace",
+                self.name
+            ));
+        }
+
+        if self.data.trim().is_empty() {
+            return invalid(format!("{} record data is empty", self.record_type));
+        }
+
+        if let Some(ttl) = self.ttl {
+            if ttl <= 0 {
+                return invalid(format!("DNS record TTL must be positive, got {}", ttl));
+            }
+        }
+
+        match (&self.record_type, self.priority) {
+            (DNStype::MX | DNStype::SRV, None) => {
+                return invalid(format!("{} records require a priority", self.record_type))
+            }
+            (DNStype::MX | DNStype::SRV, Some(priority)) if !(0..=65535).contains(&priority) => {
+                return invalid(format!(
+                    "DNS record priority must be between 0 and 65535, got {}",
+                    priority
+                ))
+            }
+            (DNStype::MX | DNStype::SRV | DNStype::Other(_), Some(_)) => {}
+            (record_type, Some(_)) => {
+                return invalid(format!("{} records do not take a priority", record_type))
+            }
+            (_, None) => {}
+        }
+
+        match self.record_type {
+            DNStype::A if self.data.parse::<Ipv4Addr>().is_err() => {
+                invalid(format!("{:?} is not an IPv4 address", self.data))
+            }
+            DNStype::AAAA if self.data.parse::<Ipv6Addr>().is_err() => {
+                invalid(format!("{:?} is not an IPv6 address", self.data))
+            }
+            DNStype::SRV if self.data.split_whitespace().count() != 3 => invalid(format!(
+                "SRV record data must be \"weight port target\", got {:?}",
+                self.data
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Copy an existing record into a builder, e.g. to re-create it elsewhere.
+///
+/// The priority is only kept for record types that use one, since the API
+/// reports `0` for every other record.
+impl From<&DNSrecord> for DNSrecordBuilder {
+    fn from(record: &DNSrecord) -> DNSrecordBuilder {
+        let priority = match record.record_type {
+            DNStype::MX | DNStype::SRV | DNStype::Other(_) => record.priority,
+            _ => None,
+        };
+
+        DNSrecordBuilder {
+            record_type: record.record_type.clone(),
+            name: record.name.clone(),
+            data: record.data.clone(),
+            priority,
+            ttl: Some(record.ttl),
+        }
+    }
+}
+
+impl From<DNSrecord> for DNSrecordBuilder {
+    fn from(record: DNSrecord) -> DNSrecordBuilder {
+        DNSrecordBuilder::from(&record)
+    }
+}
+
+/// DNS record types supported by omg.lol DNS routing.
+///
+/// Types this crate does not know about are kept in `Other`, so they
+/// survive a round trip through the API unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DNStype {
     A,
-    AAA,
+    AAAA,
     CAA,
     CNAME,
     MX,
     NS,
     SRV,
     TXT,
+    Other(String),
 }
 
 impl Display for DNStype {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", &self)
+        match self {
+            DNStype::Other(record_type) => write!(f, "{}", record_type),
+            record_type => write!(f, "{:?}", record_type),
+        }
+    }
+}
+
+impl FromStr for DNStype {
+    type Err = OmglolError;
+
+    /// Parse a record type, case-insensitively. Unknown types become
+    /// `DNStype::Other`; only an empty string is rejected.
+    fn from_str(record_type: &str) -> Result<DNStype, OmglolError> {
+        let record_type = record_type.trim();
+
+        Ok(match record_type.to_ascii_uppercase().as_str() {
+            "" => {
+                return Err(OmglolError::Validation(
+                    "DNS record type is empty".to_string(),
+                ))
+            }
+            "A" => DNStype::A,
+            "AAAA" => DNStype::AAAA,
+            "CAA" => DNStype::CAA,
+            "CNAME" => DNStype::CNAME,
+            "MX" => DNStype::MX,
+            "NS" => DNStype::NS,
+            "SRV" => DNStype::SRV,
+            "TXT" => DNStype::TXT,
+            _ => DNStype::Other(record_type.to_string()),
+        })
+    }
+}
+
+impl Serialize for DNStype {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DNStype {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DNStype, D::Error> {
+        let record_type = String::deserialize(deserializer)?;
+        record_type.parse().map_err(de::Error::custom)
     }
 }
 
@@ -180,7 +477,10 @@ pub struct ForwardingAddresses {
     /// Status message returned by the API
     pub message: String,
     pub destination_string: String,
-    pub destination_array: Vec<String>,
+
+    /// Addresses mail is forwarded to, empty if forwarding is off
+    #[serde(default, deserialize_with = "destinations")]
+    pub destination_array: Vec<EmailAddress>,
     pub address: String,
     pub email_address: String,
 }
@@ -188,7 +488,8 @@ pub struct ForwardingAddresses {
 /// Struct for when the server returns a String message
 #[derive(Deserialize, Debug)]
 pub struct MessageResponse {
-    /// Status message returned by the API
+    /// Status message returned by the API, empty if the response had no body
+    #[serde(default)]
     pub message: String,
 }
 
@@ -197,6 +498,41 @@ pub struct MessageResponse {
 pub struct RequestResponse<T> {
     pub request: RequestStatus,
     pub response: T,
+
+    #[serde(skip)]
+    pub(crate) metadata: ResponseMetadata,
+}
+
+impl<T> RequestResponse<T> {
+    /// HTTP status, headers and timing of the response.
+    pub fn metadata(&self) -> &ResponseMetadata {
+        &self.metadata
+    }
+
+    /// Replace the response body, keeping the request status and metadata.
+    pub(crate) fn map<U>(self, f: impl FnOnce(T) -> U) -> RequestResponse<U> {
+        RequestResponse {
+            request: self.request,
+            response: f(self.response),
+            metadata: self.metadata,
+        }
+    }
+}
+
+/// HTTP details of a successful response
+#[derive(Debug, Clone, Default)]
+pub struct ResponseMetadata {
+    /// HTTP status code of the response
+    pub status_code: u16,
+
+    /// Response headers
+    pub headers: HeaderMap,
+
+    /// Time from the first attempt to the response, including retries
+    pub duration: Duration,
+
+    /// Number of attempts made, `1` unless the request was retried
+    pub attempts: u32,
 }
 
 /// Status of a request
@@ -212,6 +548,7 @@ pub struct RequestStatus {
 #[derive(Deserialize, Debug)]
 pub struct TimeStrings {
     /// String of seconds elapsed since the UNIX epoch (1970-01-01 00:00:00 UTC)
+    #[serde(deserialize_with = "string_or_number")]
     pub unix_epoch_time: String,
     /// Time in [ISO 8601](https://www.iso.org/iso-8601-date-and-time-format.html) format.
     pub iso_8601_time: String,
@@ -226,28 +563,63 @@ pub struct TimeStrings {
 /// Response to a query to a Now page endpoint
 #[derive(Deserialize, Debug)]
 pub struct NowResponse {
+    /// Status message returned by the API
     pub message: String,
-    pub now: Vec<NowPage>,
+    pub now: NowPage,
 }
 
 /// Now page
 #[derive(Deserialize, Debug)]
 pub struct NowPage {
+    /// Markdown content of the page
     pub content: String,
-    pub updated: u32,
-    pub listed: u8,
-    pub nudge: u8,
+
+    /// Unix time of the last update
+    pub updated: i64,
+
+    /// `true` if the page is listed in the Now Garden
+    #[serde(deserialize_with = "flag")]
+    pub listed: bool,
+
+    /// `true` if the owner is reminded to update the page
+    #[serde(default, deserialize_with = "flag")]
+    pub nudge: bool,
+
+    /// Page metadata, as a JSON string
+    #[serde(default)]
     pub metadata: String,
 }
 
+/// Content of a Now page to be published.
+#[derive(Serialize, Debug)]
+pub struct NowPageUpdate {
+    pub content: String,
+
+    /// List the page in the Now Garden
+    #[serde(serialize_with = "flag_as_number")]
+    pub listed: bool,
+}
+
+impl NowPageUpdate {
+    /// Create a new `NowPageUpdate`.
+    ///
+    /// Arguments:
+    /// * `content`, the Markdown content of your Now page
+    /// * `listed`, `true` to show the page in the Now Garden
+    pub fn new(content: String, listed: bool) -> NowPageUpdate {
+        NowPageUpdate { content, listed }
+    }
+}
+
 /// Response to a Now Garden endpoint
 #[derive(Deserialize, Debug)]
 pub struct NowGardenResponse {
+    /// Status message returned by the API
     pub message: String,
     pub garden: Vec<NowGarden>,
 }
 
-/// A Now Garden
+/// A listed Now page in the Now Garden
 #[derive(Deserialize, Debug)]
 pub struct NowGarden {
     pub address: String,
@@ -255,19 +627,45 @@ pub struct NowGarden {
     pub updated: TimeStrings,
 }
 
-#[derive(Deserialize, Debug)]
+/// OAuth access token granted by an omg.lol member
+#[derive(Deserialize, Clone)]
 pub struct Token {
+    /// Bearer token to authenticate requests with
     pub access_token: String,
+
+    /// Token type, always `Bearer`
     pub token_type: String,
+
+    /// Scope granted by the member
     pub scope: String,
 }
 
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("access_token", &"<redacted>")
+            .field("token_type", &self.token_type)
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
 /// Response for a Paste request
 #[derive(Deserialize, Debug)]
 pub struct PasteResponse {
     /// Status message returned by the API
     pub message: String,
-    pub pastebin: Paste,
+    pub paste: Paste,
+}
+
+/// Response to a paste upload
+#[derive(Deserialize, Debug)]
+pub struct PasteUploadResponse {
+    /// Status message returned by the API
+    pub message: String,
+
+    /// Title of the saved paste
+    pub title: String,
 }
 
 /// Response for a Pastebin request
@@ -275,29 +673,41 @@ pub struct PasteResponse {
 pub struct PastebinResponse {
     /// Status message returned by the API
     pub message: String,
-    pub success: Vec<Paste>,
+    pub pastebin: Vec<Paste>,
 }
 
 /// Pastebin paste
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Paste {
     pub title: String,
     pub content: String,
 
-    #[serde(skip_serializing)]
-    pub modified_on: Option<String>,
+    /// Unix time of the last change
+    #[serde(default, deserialize_with = "optional_timestamp", skip_serializing)]
+    pub modified_on: Option<i64>,
+
+    /// `true` if the paste is shown on the public pastebin
+    #[serde(default, deserialize_with = "flag", serialize_with = "flag_as_number")]
+    pub listed: bool,
 }
 
 /// A Paste from [paste.lol](https://paste.lol)
 impl Paste {
-    /// New instance of `Paste`
+    /// New unlisted instance of `Paste`
     pub fn new(title: String, content: String) -> Paste {
         Paste {
             title,
             content,
             modified_on: None,
+            listed: false,
         }
     }
+
+    /// Show the paste on the public pastebin, or hide it.
+    pub fn listed(mut self, listed: bool) -> Paste {
+        self.listed = listed;
+        self
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -314,11 +724,38 @@ pub struct PurlsResponse {
     pub purls: Vec<Purl>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Purl {
     pub name: String,
-    pub url: String,
+    pub url: Url,
     pub counter: Option<i32>,
+
+    /// `true` if the PURL is shown on the address' public PURL list
+    #[serde(default, deserialize_with = "flag")]
+    pub listed: bool,
+}
+
+/// PURL to be created.
+#[derive(Serialize, Debug)]
+pub struct PurlUpdate {
+    pub name: String,
+    pub url: Url,
+
+    /// Show the PURL on the address' public PURL list
+    #[serde(serialize_with = "flag_as_number")]
+    pub listed: bool,
+}
+
+impl PurlUpdate {
+    /// Create a new `PurlUpdate`.
+    ///
+    /// Arguments:
+    /// * `name`, the name of the PURL, e.g. `rust` for `foobar.url.lol/rust`
+    /// * `url`, the URL the PURL redirects to
+    /// * `listed`, `true` to show the PURL on the public PURL list
+    pub fn new(name: String, url: Url, listed: bool) -> PurlUpdate {
+        PurlUpdate { name, url, listed }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -338,7 +775,7 @@ pub trait ContentAsJSON {
 
 impl ContentAsJSON for String {
     fn json_content(&self) -> String {
-        format!("{{\"content\": \"{}\"}}", &self)
+        serde_json::json!({ "content": self }).to_string()
     }
 }
 
@@ -366,14 +803,31 @@ pub struct StatuslogAllStatuses {
     pub statuses: Vec<Status>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+/// Response to a request for a single status, e.g. an address' latest one
+#[derive(Deserialize, Debug)]
+pub struct StatuslogStatus {
+    /// Status message returned by the API
+    pub message: String,
+    pub status: Status,
+}
+
+/// Statuses from every address on the statuslog, newest first
+#[derive(Deserialize, Debug)]
+pub struct StatuslogTimeline {
+    /// Status message returned by the API
+    pub message: String,
+    pub statuses: Vec<Status>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Status {
     /// A status log entry.
     pub emoji: String,
     pub content: String,
     pub external_url: Option<String>,
 
-    #[serde(skip_serializing)]
+    /// Status ID, only sent when updating a status
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub id: String,
 
     #[serde(skip_serializing)]
@@ -434,7 +888,7 @@ impl StatuslogBio {
 
 impl ContentAsJSON for StatuslogBio {
     fn json_content(&self) -> String {
-        format!("{{\"content\": \"{}\"}}", &self.message)
+        self.bio.json_content()
     }
 }
 
@@ -445,7 +899,7 @@ pub struct StatusPostResponse {
     pub id: String,
     pub status: String,
     pub url: String,
-    pub external_url: String,
+    pub external_url: Option<String>,
 }
 
 /// Profile themes
@@ -616,18 +1070,3 @@ pub struct WeblogTemplateResponse {
     pub message: String,
     pub template: String,
 }
-
-/// An HTTP request error
-#[derive(Debug, Clone)]
-pub struct RequestError {
-    /// HTTP status code as defined in [RFC 9110](https://httpwg.org/specs/rfc9110.html#overview.of.status.codes).
-    pub status_code: u16,
-}
-
-impl Error for RequestError {}
-
-impl fmt::Display for RequestError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Request error. HTTP status code: {}.", &self.status_code)
-    }
-}
@@ -0,0 +1,71 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Telemetry - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! `tracing` instrumentation of API requests, enabled by the `tracing`
+//! feature.
+//!
+//! Spans only carry the route template, never the full path, headers or
+//! bodies, so API keys, OAuth secrets and member content stay out of logs.
+
+use std::{error::Error, time::Instant};
+
+use reqwest::Method;
+use tracing::{field, Span};
+
+use crate::client::{RawResponse, Route};
+use crate::error::OmglolError;
+
+/// Span covering a request and its retries.
+pub(crate) fn request_span(method: &Method, route: &Route) -> Span {
+    tracing::info_span!(
+        "omglol.request",
+        method = %method,
+        route = route.template,
+        address = route.address(),
+        status = field::Empty,
+        latency_ms = field::Empty,
+        attempts = field::Empty,
+    )
+}
+
+/// Record the status and latency of a finished request on its span.
+pub(crate) fn record_outcome(
+    span: &Span,
+    result: &Result<RawResponse, OmglolError>,
+    started: Instant,
+) {
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    match result {
+        Ok(raw_res) => {
+            span.record("status", raw_res.metadata.status_code);
+            tracing::debug!(parent: span, "request succeeded");
+        }
+        Err(error) => {
+            if let Some(status_code) = error.status_code() {
+                span.record("status", status_code);
+            }
+            tracing::warn!(parent: span, error = %redacted(error), "request failed");
+        }
+    }
+}
+
+/// Describe `error` without the request URL, whose query may hold secrets.
+pub(crate) fn redacted(error: &OmglolError) -> String {
+    match error {
+        OmglolError::Transport(error) => match error.source() {
+            Some(source) => format!("Transport error: {}", source),
+            None => "Transport error".to_string(),
+        },
+        error => error.to_string(),
+    }
+}
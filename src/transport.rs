@@ -0,0 +1,228 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Transport - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! HTTP layer `OmglolClient` sends its requests through.
+//!
+//! [`ReqwestTransport`] is used by default. Set another [`Transport`] with
+//! [`OmglolClientBuilder::transport`](crate::client::OmglolClientBuilder::transport),
+//! e.g. a [`MemoryTransport`] to test code built on the client without
+//! network access:
+//!
+//! ```rust
+//! use omglol::{
+//!     transport::{HttpResponse, MemoryTransport},
+//!     OmglolClient,
+//! };
+//! use reqwest::Method;
+//!
+//! # async fn run() {
+//! let transport = MemoryTransport::new().respond(
+//!     Method::GET,
+//!     "service/info",
+//!     HttpResponse::new(200, r#"{"request": {"status_code": 200, "success": true},
+//!         "response": {"message": "Hi", "members": 1, "addresses": 1, "profiles": 1}}"#),
+//! );
+//! let client = OmglolClient::builder().transport(transport.clone()).build();
+//!
+//! assert_eq!(client.service_status().await.unwrap().response.members, 1);
+//! assert_eq!(transport.requests()[0].path, "service/info");
+//! # }
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client, Method,
+};
+
+use crate::error::OmglolError;
+
+/// Request ready to be sent.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+
+    /// Full URL, base URL included
+    pub url: String,
+
+    /// Percent-encoded path relative to the base URL, e.g. `address/foobar/dns`
+    pub path: String,
+
+    /// Headers, including `Authorization` for authenticated requests
+    pub headers: HeaderMap,
+
+    pub body: Option<String>,
+}
+
+/// Response as received, whatever its status.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status_code: u16,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Create a response with `status_code`, `body` and no headers.
+    pub fn new(status_code: u16, body: impl Into<String>) -> HttpResponse {
+        HttpResponse {
+            status_code,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Add the header `name: value`.
+    ///
+    /// Arguments:
+    /// * `name` - header name, e.g. `Retry-After`
+    /// * `value` - header value; invalid names or values are ignored
+    pub fn header(mut self, name: &str, value: &str) -> HttpResponse {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            self.headers.append(name, value);
+        }
+        self
+    }
+}
+
+/// Future returned by [`Transport::send`]
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<HttpResponse, OmglolError>> + Send + 'a>>;
+
+/// Sends requests over HTTP, or pretends to.
+///
+/// Errors should only be returned when no response was received at all. A
+/// custom transport can wrap its errors in [`OmglolError::Io`]; connection
+/// and timeout errors are retried like those of reqwest.
+pub trait Transport: Debug + Send + Sync {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        (**self).send(request)
+    }
+}
+
+/// Default transport, backed by a `reqwest::Client`.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Send requests with an existing `reqwest::Client`.
+    pub fn new(client: Client) -> ReqwestTransport {
+        ReqwestTransport { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let mut req = self
+                .client
+                .request(request.method, &request.url)
+                .headers(request.headers);
+            if let Some(body) = request.body {
+                req = req.body(body);
+            }
+
+            let resp = req.send().await?;
+            let status_code = resp.status().as_u16();
+            let headers = resp.headers().clone();
+            let body = resp.text().await?;
+
+            Ok(HttpResponse {
+                status_code,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+/// In-memory transport serving canned responses keyed by method and path.
+///
+/// Clones share their responses and the log of requests received. A request
+/// with no canned response gets a `404`.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransport {
+    responses: Arc<Mutex<HashMap<(Method, String), HttpResponse>>>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> MemoryTransport {
+        MemoryTransport::default()
+    }
+
+    /// Answer `method` requests to `path` with `response`.
+    ///
+    /// Arguments:
+    /// * `method` - HTTP method
+    /// * `path` - percent-encoded path relative to the base URL, e.g.
+    ///   `address/foobar/dns`. The query string is not part of the path, so
+    ///   requests match whatever their query.
+    /// * `response` - response to serve every time
+    pub fn respond(self, method: Method, path: &str, response: HttpResponse) -> MemoryTransport {
+        self.responses
+            .lock()
+            .unwrap()
+            .insert((method, path.trim_start_matches('/').to_string()), response);
+        self
+    }
+
+    /// Requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn response_for(&self, method: &Method, path: &str) -> Option<HttpResponse> {
+        self.responses
+            .lock()
+            .unwrap()
+            .get(&(method.clone(), path.to_string()))
+            .cloned()
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        let response = self
+            .response_for(&request.method, &request.path)
+            .unwrap_or_else(|| {
+                let message = format!("No canned response for {} {}", request.method, request.path);
+                HttpResponse::new(
+                    404,
+                    serde_json::json!({
+                        "request": {"status_code": 404, "success": false},
+                        "response": {"message": message},
+                    })
+                    .to_string(),
+                )
+            });
+        self.requests.lock().unwrap().push(request);
+        Box::pin(async move { Ok(response) })
+    }
+}
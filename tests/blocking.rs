@@ -0,0 +1,59 @@
+mod common;
+
+use common::{mock_server, ok, replay};
+use omglol::{blocking, OmglolClient};
+
+use serde_json::json;
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer,
+};
+
+/// Start a mock server from outside any async context.
+fn start(runtime: &tokio::runtime::Runtime) -> MockServer {
+    runtime.block_on(mock_server())
+}
+
+#[test]
+fn public_endpoint() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = start(&runtime);
+    runtime.block_on(
+        Mock::given(method("GET"))
+            .and(path("/service/info"))
+            .respond_with(ok(json!({
+                "message": "omg.lol is home to 1,000 members.",
+                "members": 1000,
+                "addresses": 1500,
+                "profiles": 900,
+            })))
+            .expect(1)
+            .mount(&server),
+    );
+
+    let client =
+        blocking::OmglolClient::from_async(OmglolClient::builder().base_url(server.uri()).build())
+            .unwrap();
+    assert_eq!(client.service_status().unwrap().response.members, 1000);
+}
+
+#[test]
+fn authenticated_endpoint() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = start(&runtime);
+    runtime.block_on(
+        Mock::given(method("GET"))
+            .and(path("/address/foobar/now"))
+            .and(header("Authorization", "Bearer test-api-key"))
+            .respond_with(replay("get_now_page"))
+            .expect(1)
+            .mount(&server),
+    );
+
+    let client =
+        blocking::OmglolClient::from_async(OmglolClient::builder().base_url(server.uri()).build())
+            .unwrap()
+            .auth("test-api-key".to_string());
+    let now = client.get_now_page("foobar").unwrap().response.now;
+    assert!(now.listed);
+}
@@ -0,0 +1,59 @@
+// SPDX-LICENSE-IDENTIFIER: MPL-2.0
+
+/*
+Test helpers - omglol crate for Rust
+
+Copyright © 2023 Gil Poiares-Oliveira <gil@poiares-oliveira.com>.
+All rights reserved.
+
+This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0.
+ If a copy of the MPL was not distributed with this file,
+ You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+//! Local stand-in for api.omg.lol used by the integration tests.
+
+#![allow(dead_code)]
+
+use serde_json::{json, Value};
+use wiremock::{MockServer, ResponseTemplate};
+
+/// Wrap `response` in the `{"request": ..., "response": ...}` envelope the
+/// API uses for every reply.
+pub fn envelope(status_code: u16, response: Value) -> Value {
+    json!({
+        "request": {
+            "status_code": status_code,
+            "success": (200..300).contains(&status_code),
+        },
+        "response": response,
+    })
+}
+
+/// Successful JSON reply carrying `response` in the API envelope.
+pub fn ok(response: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(envelope(200, response))
+}
+
+/// Start a new mock server.
+pub async fn mock_server() -> MockServer {
+    MockServer::start().await
+}
+
+/// Load the recorded API response `tests/fixtures/<name>.json`.
+pub fn fixture(name: &str) -> Value {
+    let path = format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let fixture = std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("missing {}", path));
+    serde_json::from_str(&fixture).unwrap_or_else(|error| panic!("invalid {}: {}", path, error))
+}
+
+/// Reply with the recorded API response `tests/fixtures/<name>.json`.
+pub fn replay(name: &str) -> ResponseTemplate {
+    let fixture = fixture(name);
+    let status_code = fixture["request"]["status_code"].as_u64().unwrap_or(200);
+    ResponseTemplate::new(status_code as u16).set_body_json(fixture)
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use common::{mock_server, ok};
+use omglol::{
+    credentials::{ConfigFile, CredentialProvider, EnvVar, OAuthToken, StaticKey},
+    structures::Token,
+    OmglolClient, OmglolError,
+};
+
+use serde_json::json;
+use std::{env, fs, path::PathBuf, time::Duration};
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer,
+};
+
+fn token(access_token: &str) -> Token {
+    Token {
+        access_token: access_token.to_string(),
+        token_type: "Bearer".to_string(),
+        scope: "everything".to_string(),
+    }
+}
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("omglol-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+async fn expect_bearer(server: &MockServer, api_key: &str) {
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/dns"))
+        .and(header(
+            "Authorization",
+            format!("Bearer {}", api_key).as_str(),
+        ))
+        .respond_with(ok(json!({"message": "Your DNS records.", "dns": []})))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[test]
+fn static_key() {
+    assert_eq!(
+        StaticKey::new("abc").api_key().unwrap().as_deref(),
+        Some("abc")
+    );
+}
+
+#[test]
+fn env_var() {
+    let name = format!("OMGLOL_TEST_API_KEY_{}", std::process::id());
+    assert_eq!(EnvVar::named(&name).api_key().unwrap(), None);
+
+    env::set_var(&name, "from-env");
+    assert_eq!(
+        EnvVar::named(&name).api_key().unwrap().as_deref(),
+        Some("from-env")
+    );
+    env::remove_var(&name);
+}
+
+#[test]
+fn config_file_formats() {
+    for (name, contents) in [
+        (
+            "toml",
+            "# omg.lol\naddress = \"foobar\"\napi_key = \"from-file\"\n",
+        ),
+        (
+            "dotenv",
+            "OMGLOL_ADDRESS=foobar\nOMGLOL_API_KEY=from-file\n",
+        ),
+        ("bare", "\nfrom-file\n"),
+    ] {
+        let path = temp_file(name, contents);
+        assert_eq!(
+            ConfigFile::new(&path).api_key().unwrap().as_deref(),
+            Some("from-file"),
+            "{} config",
+            name
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    let path = temp_file("no-key", "address = \"foobar\"\n");
+    assert_eq!(ConfigFile::new(&path).api_key().unwrap(), None);
+    fs::remove_file(path).unwrap();
+
+    assert!(matches!(
+        ConfigFile::new("/nonexistent/omglol").api_key(),
+        Err(OmglolError::Io(_))
+    ));
+}
+
+#[test]
+fn config_file_is_cached_until_modified() {
+    let path = temp_file("cached", "api_key = \"key-1\"\n");
+    let provider = ConfigFile::new(&path);
+    assert_eq!(provider.api_key().unwrap().as_deref(), Some("key-1"));
+
+    let modified = fs::metadata(&path).unwrap().modified().unwrap();
+    fs::write(&path, "api_key = \"key-2\"\n").unwrap();
+    let file = fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(modified).unwrap();
+    assert_eq!(provider.api_key().unwrap().as_deref(), Some("key-1"));
+
+    file.set_modified(modified + Duration::from_secs(1))
+        .unwrap();
+    assert_eq!(provider.api_key().unwrap().as_deref(), Some("key-2"));
+    fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn config_file_is_read_per_request() {
+    let server = mock_server().await;
+    let path = temp_file("rotated", "api_key = \"first\"\n");
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth_with(ConfigFile::new(&path));
+
+    expect_bearer(&server, "first").await;
+    client.get_dns_records("foobar").await.unwrap();
+
+    fs::write(&path, "api_key = \"second\"\n").unwrap();
+    expect_bearer(&server, "second").await;
+    client.get_dns_records("foobar").await.unwrap();
+    fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn oauth_token_refresh_reaches_clones() {
+    let server = mock_server().await;
+    let credentials = OAuthToken::new(token("old-token"));
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth_with(credentials.clone());
+    let clone = client.clone();
+
+    expect_bearer(&server, "old-token").await;
+    client.get_dns_records("foobar").await.unwrap();
+
+    credentials.refresh(token("new-token"));
+    expect_bearer(&server, "new-token").await;
+    clone.get_dns_records("foobar").await.unwrap();
+}
+
+#[tokio::test]
+async fn closure_provider_without_key() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth_with(|| Ok(None));
+
+    assert!(matches!(
+        client.get_dns_records("foobar").await,
+        Err(OmglolError::MissingApiKey { .. })
+    ));
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use common::{mock_server, ok};
+use omglol::{
+    dns::sync::{self, SyncAction, SyncPlan},
+    structures::{DNSrecord, DNSrecordBuilder, DNStype},
+    OmglolClient,
+};
+
+use serde_json::json;
+use wiremock::{
+    matchers::{body_json, method, path},
+    Mock, ResponseTemplate,
+};
+
+fn record(id: i32, record_type: &str, name: &str, data: &str, ttl: i32) -> DNSrecord {
+    serde_json::from_value(json!({
+        "id": id,
+        "type": record_type,
+        "name": name,
+        "data": data,
+        "priority": 0,
+        "ttl": ttl,
+    }))
+    .unwrap()
+}
+
+fn current_zone() -> Vec<DNSrecord> {
+    vec![
+        record(1, "A", "foobar", "192.0.2.1", 3600),
+        record(2, "CNAME", "www.foobar", "foobar.omg.lol.", 3600),
+        record(3, "TXT", "foobar", "old", 3600),
+        record(4, "TXT", "_stale.foobar", "remove me", 3600),
+    ]
+}
+
+fn desired_zone() -> Vec<DNSrecordBuilder> {
+    vec![
+        DNSrecordBuilder::new(DNStype::A, "@".to_string(), "192.0.2.1".to_string()),
+        DNSrecordBuilder::new(
+            DNStype::CNAME,
+            "www".to_string(),
+            "FOOBAR.omg.lol".to_string(),
+        ),
+        DNSrecordBuilder::new(DNStype::TXT, "".to_string(), "new".to_string()).ttl(300),
+        DNSrecordBuilder::new(DNStype::MX, "".to_string(), "mx.example.com".to_string())
+            .priority(10),
+    ]
+}
+
+#[test]
+fn plan_diffs_desired_against_current() {
+    let plan = SyncPlan::new("foobar", &current_zone(), desired_zone());
+
+    assert!(matches!(&plan.actions[0], SyncAction::Unchanged(record) if record.id == 1));
+    assert!(matches!(&plan.actions[1], SyncAction::Unchanged(record) if record.id == 2));
+    assert!(matches!(
+        &plan.actions[2],
+        SyncAction::Update { current, desired } if current.id == 3 && desired.data == "new"
+    ));
+    assert!(matches!(
+        &plan.actions[3],
+        SyncAction::Create(desired) if desired.record_type == DNStype::MX
+    ));
+    assert!(matches!(&plan.actions[4], SyncAction::Delete(record) if record.id == 4));
+    assert_eq!(plan.actions.len(), 5);
+    assert!(plan.has_changes());
+}
+
+#[test]
+fn plan_without_changes() {
+    let current = current_zone();
+    let plan = SyncPlan::new("foobar", &current, &current);
+
+    assert!(!plan.has_changes());
+}
+
+#[test]
+fn dry_run_output() {
+    let plan = SyncPlan::new("foobar", &current_zone(), desired_zone());
+
+    assert_eq!(
+        plan.to_string(),
+        "DNS plan for foobar:
+  = A @ ttl=3600 \"192.0.2.1\"
+  = CNAME www ttl=3600 \"foobar.omg.lol.\"
+  ~ TXT @ ttl=3600 \"old\" -> TXT @ ttl=300 \"new\"
+  + MX @ priority=10 \"mx.example.com\"
+  - TXT _stale ttl=3600 \"remove me\"
+"
+    );
+}
+
+#[tokio::test]
+async fn apply_reports_each_record() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/dns"))
+        .respond_with(ok(json!({
+            "message": "Your DNS records were retrieved.",
+            "dns": current_zone()
+                .iter()
+                .map(|record| json!({
+                    "id": record.id,
+                    "type": record.record_type,
+                    "name": record.name,
+                    "data": record.data,
+                    "priority": record.priority,
+                    "ttl": record.ttl,
+                }))
+                .collect::<Vec<_>>(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/address/foobar/dns/4"))
+        .respond_with(ok(json!({
+            "message": "Your DNS record was deleted.",
+            "dns": [],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/address/foobar/dns/3"))
+        .and(body_json(
+            json!({"type": "TXT", "name": "", "data": "new", "ttl": 300}),
+        ))
+        .respond_with(ok(json!({
+            "message": "Your DNS record was updated.",
+            "response_received": {"data": {
+                "id": 3, "type": "TXT", "name": "foobar", "content": "new",
+                "priority": 0, "ttl": 300,
+            }},
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/address/foobar/dns"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let plan = sync::plan(&client, "foobar", desired_zone()).await.unwrap();
+    let report = plan.apply(&client).await;
+
+    assert!(!report.is_success());
+    assert_eq!(report.results.len(), 3);
+    assert!(matches!(report.results[0].action, SyncAction::Delete(_)));
+    let failures: Vec<_> = report.failures().collect();
+    assert_eq!(failures.len(), 1);
+    assert!(matches!(failures[0].action, SyncAction::Create(_)));
+    assert_eq!(
+        failures[0].result.as_ref().unwrap_err().status_code(),
+        Some(500)
+    );
+}
@@ -0,0 +1,164 @@
+use omglol::{
+    dns::zone,
+    structures::{DNSrecord, DNSrecordBuilder, DNStype},
+};
+
+use serde_json::json;
+
+fn record(id: i32, record_type: &str, name: &str, data: &str, priority: i32) -> DNSrecord {
+    serde_json::from_value(json!({
+        "id": id,
+        "type": record_type,
+        "name": name,
+        "data": data,
+        "priority": priority,
+        "ttl": 3600,
+    }))
+    .unwrap()
+}
+
+fn records() -> Vec<DNSrecord> {
+    vec![
+        record(1, "A", "foobar", "192.0.2.1", 0),
+        record(2, "AAAA", "foobar", "2001:db8::1", 0),
+        record(3, "CAA", "foobar", "0 issue \"letsencrypt.org\"", 0),
+        record(4, "CNAME", "www.foobar", "foobar.omg.lol", 0),
+        record(5, "MX", "foobar", "mx.example.com", 10),
+        record(6, "NS", "sub.foobar", "ns1.example.com", 0),
+        record(7, "SRV", "_sip._tcp.foobar", "5 5060 sip.example.com", 20),
+        record(8, "TXT", "foobar", "v=spf1 include:\"quoted\" \\ -all", 0),
+        record(9, "TXT", "_long.foobar", &"x".repeat(300), 0),
+    ]
+}
+
+#[test]
+fn export_zone_file() {
+    let exported = zone::export("foobar", &records());
+
+    assert!(exported.contains("$ORIGIN foobar.omg.lol.\n"));
+    assert!(exported.contains("@\t3600\tIN\tA\t192.0.2.1\n"));
+    assert!(exported.contains("@\t3600\tIN\tCAA\t0 issue \"letsencrypt.org\"\n"));
+    assert!(exported.contains("www\t3600\tIN\tCNAME\tfoobar.omg.lol.\n"));
+    assert!(exported.contains("@\t3600\tIN\tMX\t10 mx.example.com.\n"));
+    assert!(exported.contains("_sip._tcp\t3600\tIN\tSRV\t20 5 5060 sip.example.com.\n"));
+    assert!(exported.contains("@\t3600\tIN\tTXT\t\"v=spf1 include:\\\"quoted\\\" \\\\ -all\"\n"));
+    assert!(exported.contains(&format!(
+        "_long\t3600\tIN\tTXT\t\"{}\" \"{}\"\n",
+        "x".repeat(255),
+        "x".repeat(45)
+    )));
+}
+
+#[test]
+fn round_trip() {
+    let records = records();
+    let exported = zone::export("foobar", &records);
+    let imported = zone::parse("foobar", &exported).unwrap();
+
+    let expected: Vec<DNSrecordBuilder> = records
+        .iter()
+        .map(|record| {
+            let mut builder = DNSrecordBuilder::from(record);
+            builder.name = omglol::dns::relative_name(&record.name, "foobar");
+            builder
+        })
+        .collect();
+    assert_eq!(imported, expected);
+}
+
+#[test]
+fn non_ascii_txt() {
+    let records = vec![record(1, "TXT", "foobar", "café ☕ \"ünïcode\"", 0)];
+    let exported = zone::export("foobar", &records);
+    let imported = zone::parse("foobar", &exported).unwrap();
+    assert_eq!(imported[0].data, records[0].data);
+
+    let escaped = zone::parse("foobar", "@ 300 IN TXT \"caf\\195\\169\" caf\\195\\169\n").unwrap();
+    assert_eq!(escaped[0].data, "cafécafé");
+
+    assert!(zone::parse("foobar", "@ 300 IN TXT \"caf\\195\"\n").is_err());
+    assert!(zone::parse("foobar", "@ 300 IN TXT \"\\999\"\n").is_err());
+}
+
+#[test]
+fn skips_records_managed_by_omg_lol() {
+    let zone_file = "$ORIGIN foobar.omg.lol.
+@ IN SOA ns1.example.com. hostmaster.example.com. (
+    2023022801 3600 600 604800 300 )
+@ IN NS ns1.example.com.
+sub IN NS ns1.example.com.
+@ IN A 192.0.2.1
+";
+    let records = zone::parse("foobar", zone_file).unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].record_type, DNStype::NS);
+    assert_eq!(records[0].name, "sub");
+    assert_eq!(records[1].record_type, DNStype::A);
+}
+
+#[test]
+fn parse_zone_file_syntax() {
+    let zone_file = r#"
+; Zone migrated from another provider
+$ORIGIN foobar.omg.lol.
+$TTL 1h
+@           IN  A       192.0.2.1   ; apex
+            IN  AAAA    2001:db8::1
+www     300 IN  CNAME   @
+mail        IN  MX      ( 10
+                          mx.example.net. )
+blog.foobar.omg.lol. 1d IN TXT "part one " "part two" "\065"
+$ORIGIN sub.foobar.omg.lol.
+api         IN  CNAME   backend
+"#;
+
+    let records = zone::parse("foobar", zone_file).unwrap();
+    let summary: Vec<_> = records
+        .iter()
+        .map(|r| {
+            (
+                r.record_type.clone(),
+                r.name.as_str(),
+                r.data.as_str(),
+                r.priority,
+                r.ttl,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            (DNStype::A, "", "192.0.2.1", None, Some(3600)),
+            (DNStype::AAAA, "", "2001:db8::1", None, Some(3600)),
+            (DNStype::CNAME, "www", "foobar.omg.lol", None, Some(300)),
+            (DNStype::MX, "mail", "mx.example.net", Some(10), Some(3600)),
+            (
+                DNStype::TXT,
+                "blog",
+                "part one part twoA",
+                None,
+                Some(86400)
+            ),
+            (
+                DNStype::CNAME,
+                "api.sub",
+                "backend.sub.foobar.omg.lol",
+                None,
+                Some(3600)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn parse_errors_name_the_line() {
+    let error = zone::parse("foobar", "@ IN A 192.0.2.1\n@ IN A not-an-address\n").unwrap_err();
+    assert!(error.to_string().contains("line 2"));
+
+    assert!(zone::parse("foobar", "@ IN TXT \"unterminated\n").is_err());
+    assert!(zone::parse("foobar", "@ IN MX ( 10 mx.example.com.\n").is_err());
+    assert!(zone::parse("foobar", "$INCLUDE other.zone\n").is_err());
+    assert!(zone::parse("foobar", "  IN A 192.0.2.1\n").is_err());
+}
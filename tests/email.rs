@@ -0,0 +1,114 @@
+mod common;
+
+use common::{mock_server, ok};
+use omglol::{
+    email::{format_addresses_string, parse_destinations},
+    structures::ForwardingAddresses,
+    OmglolClient, OmglolError,
+};
+
+use email_address::EmailAddress;
+use serde_json::json;
+use std::str::FromStr;
+use wiremock::{
+    matchers::{body_json, method, path},
+    Mock,
+};
+
+fn email(address: &str) -> EmailAddress {
+    EmailAddress::from_str(address).unwrap()
+}
+
+#[test]
+fn formats_addresses() {
+    assert_eq!(
+        format_addresses_string(&[email("foo@example.com")]).unwrap(),
+        r#"{"destination":"foo@example.com"}"#
+    );
+    assert_eq!(
+        format_addresses_string(&[email("foo@example.com"), email("bar@example.com")]).unwrap(),
+        r#"{"destination":"foo@example.com, bar@example.com"}"#
+    );
+    assert_eq!(
+        format_addresses_string(&[]).unwrap(),
+        r#"{"destination":""}"#
+    );
+}
+
+#[test]
+fn parses_destinations() {
+    let destinations = parse_destinations(&[" foo@example.com ", "bar@example.com"]).unwrap();
+    assert_eq!(destinations[0].as_str(), "foo@example.com");
+
+    match parse_destinations(&["foo@example.com", "nope"]) {
+        Err(OmglolError::Validation(reason)) => assert!(reason.contains("\"nope\"")),
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn cleared_forwarding_decodes() {
+    let forwarding: ForwardingAddresses = serde_json::from_value(json!({
+        "message": "Your email forwarding settings were retrieved.",
+        "destination_string": "",
+        "destination_array": [""],
+        "address": "foobar",
+        "email_address": "foobar@omg.lol",
+    }))
+    .unwrap();
+    assert!(forwarding.destination_array.is_empty());
+}
+
+#[tokio::test]
+async fn clear_forwarding_addresses() {
+    let server = mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/address/foobar/email"))
+        .and(body_json(json!({"destination": ""})))
+        .respond_with(ok(json!({
+            "message": "Your email forwarding settings have been updated.",
+            "destination_string": "",
+            "destination_array": [],
+            "address": "foobar",
+            "email_address": "foobar@omg.lol",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+
+    let forwarding = client
+        .clear_forwarding_addresses("foobar")
+        .await
+        .unwrap()
+        .response;
+    assert!(forwarding.destination_array.is_empty());
+}
+
+#[tokio::test]
+async fn invalid_destinations_are_not_sent() {
+    let server = mock_server().await;
+    Mock::given(method("POST"))
+        .respond_with(ok(json!({})))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+
+    assert!(matches!(
+        client.set_forwarding_addresses("foobar", &[]).await,
+        Err(OmglolError::Validation(_))
+    ));
+    assert!(matches!(
+        client
+            .set_forwarding_addresses("foobar", &[email("FooBar@omg.lol")])
+            .await,
+        Err(OmglolError::Validation(_))
+    ));
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::{envelope, mock_server, ok};
+use omglol::{
+    email::{bulk, parse_destinations},
+    OmglolClient,
+};
+
+use serde_json::json;
+use wiremock::{
+    matchers::{body_json, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn forwarding(address: &str, destinations: &[&str]) -> serde_json::Value {
+    json!({
+        "message": "Your email forwarding settings were retrieved.",
+        "destination_string": destinations.join(", "),
+        "destination_array": destinations,
+        "address": address,
+        "email_address": format!("{}@omg.lol", address),
+    })
+}
+
+async fn current(server: &MockServer, address: &str, destinations: &[&str]) {
+    Mock::given(method("GET"))
+        .and(path(format!("/address/{}/email", address)))
+        .respond_with(ok(forwarding(address, destinations)))
+        .mount(server)
+        .await;
+}
+
+async fn expect_update(server: &MockServer, address: &str, destination: &str) {
+    Mock::given(method("POST"))
+        .and(path(format!("/address/{}/email", address)))
+        .and(body_json(json!({ "destination": destination })))
+        .respond_with(ok(forwarding(address, &[])))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn applies_only_changes() {
+    let server = mock_server().await;
+    current(&server, "foobar", &["sales@example.com"]).await;
+    current(&server, "barbaz", &["old@example.com"]).await;
+    current(&server, "bazqux", &["someone@example.com"]).await;
+    Mock::given(method("GET"))
+        .and(path("/address/missing/email"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(envelope(
+            404,
+            json!({"message": "That address doesn't belong to you."}),
+        )))
+        .mount(&server)
+        .await;
+    expect_update(&server, "barbaz", "support@example.com, oncall@example.com").await;
+    expect_update(&server, "bazqux", "").await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+
+    let desired = vec![
+        (
+            "foobar",
+            parse_destinations(&["Sales@example.com"]).unwrap(),
+        ),
+        (
+            "barbaz",
+            parse_destinations(&["support@example.com", "oncall@example.com"]).unwrap(),
+        ),
+        ("bazqux", Vec::new()),
+        ("missing", parse_destinations(&["x@example.com"]).unwrap()),
+    ];
+    let plan = bulk::plan(&client, desired).await;
+
+    assert!(plan.has_changes());
+    assert_eq!(
+        plan.to_string(),
+        "Email forwarding plan:\n\
+         \x20 ~ barbaz: old@example.com -> support@example.com, oncall@example.com\n\
+         \x20 ~ bazqux: someone@example.com -> (none)\n\
+         \x20 = foobar: sales@example.com\n\
+         \x20 ! missing: Request error. HTTP status code: 404. That address doesn't belong to you.\n"
+    );
+    let barbaz = &plan.addresses[0];
+    assert_eq!(barbaz.added().len(), 2);
+    assert_eq!(barbaz.removed()[0].as_str(), "old@example.com");
+
+    let report = plan.apply(&client).await;
+    assert!(!report.is_success());
+    let addresses: Vec<&str> = report
+        .results
+        .iter()
+        .map(|result| result.address.as_str())
+        .collect();
+    assert_eq!(addresses, ["barbaz", "bazqux", "foobar", "missing"]);
+    let changed: Vec<&str> = report
+        .changed()
+        .map(|result| result.address.as_str())
+        .collect();
+    assert_eq!(changed, ["barbaz", "bazqux"]);
+    let failures: Vec<&str> = report
+        .failures()
+        .map(|result| result.address.as_str())
+        .collect();
+    assert_eq!(failures, ["missing"]);
+}
+
+#[tokio::test]
+async fn self_forwarding_is_not_planned() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+
+    let plan = bulk::plan(
+        &client,
+        vec![("foobar", parse_destinations(&["foobar@omg.lol"]).unwrap())],
+    )
+    .await;
+    assert!(plan.addresses.is_empty());
+    assert_eq!(plan.failures[0].address, "foobar");
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
@@ -0,0 +1,947 @@
+//! Every endpoint method replayed against its recorded response, checking the
+//! request it sends: method, path, authentication and body.
+
+mod common;
+
+use common::{mock_server, replay};
+use omglol::{
+    client::{Auth, NoAuth},
+    structures::{DNSrecordBuilder, DNStype, NowPageUpdate, Paste, Status, StatuslogBio, Web},
+    OmglolClient, OmglolError,
+};
+
+use email_address::EmailAddress;
+use serde_json::{json, Value};
+use std::str::FromStr;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, Request,
+};
+
+const API_KEY: &str = "test-api-key";
+
+fn auth_client(server: &MockServer) -> OmglolClient<Auth> {
+    OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth(API_KEY.to_string())
+}
+
+fn public_client(server: &MockServer) -> OmglolClient<NoAuth> {
+    OmglolClient::builder().base_url(server.uri()).build()
+}
+
+/// Serve the fixture `name` for `http_method` requests to `endpoint`.
+async fn serve(server: &MockServer, http_method: &str, endpoint: &str, name: &str) {
+    Mock::given(method(http_method))
+        .and(path(endpoint))
+        .respond_with(replay(name))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+/// The single request `server` received, after checking that it carried the
+/// API key if and only if `authenticated`.
+async fn received(server: &MockServer, authenticated: bool) -> Request {
+    let mut requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1, "expected exactly one request");
+    let request = requests.remove(0);
+
+    let authorization = request
+        .headers
+        .get(&"Authorization".into())
+        .map(|values| values.as_str().to_string());
+    if authenticated {
+        assert_eq!(authorization, Some(format!("Bearer {}", API_KEY)));
+    } else {
+        assert_eq!(authorization, None, "public endpoint sent an API key");
+    }
+    request
+}
+
+fn json_body(request: &Request) -> Value {
+    serde_json::from_slice(&request.body).expect("request body is not JSON")
+}
+
+fn text_body(request: &Request) -> String {
+    String::from_utf8(request.body.clone()).unwrap()
+}
+
+// DNS
+
+#[tokio::test]
+async fn get_dns_records() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/address/foobar/dns", "get_dns_records").await;
+
+    let dns = auth_client(&server)
+        .get_dns_records("foobar")
+        .await
+        .unwrap()
+        .response
+        .dns;
+    received(&server, true).await;
+    assert_eq!(dns.len(), 3);
+    assert_eq!(dns[1].record_type, DNStype::MX);
+    assert_eq!(dns[1].priority, Some(10));
+}
+
+#[tokio::test]
+async fn create_dns_record() {
+    let server = mock_server().await;
+    serve(&server, "POST", "/address/foobar/dns", "create_dns_record").await;
+
+    let record =
+        DNSrecordBuilder::new(DNStype::TXT, "_test".to_string(), "hello world".to_string())
+            .ttl(300);
+    let response = auth_client(&server)
+        .create_dns_record("foobar", &record)
+        .await
+        .unwrap()
+        .response;
+    let request = received(&server, true).await;
+    assert_eq!(
+        json_body(&request),
+        json!({"type": "TXT", "name": "_test", "data": "hello world", "ttl": 300})
+    );
+    assert_eq!(response.record().id, 41923514);
+}
+
+#[tokio::test]
+async fn update_dns_record() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "PATCH",
+        "/address/foobar/dns/41923511",
+        "update_dns_record",
+    )
+    .await;
+
+    let record = DNSrecordBuilder::new(DNStype::A, "www".to_string(), "192.0.2.2".to_string());
+    let response = auth_client(&server)
+        .update_dns_record("foobar", 41923511, &record)
+        .await
+        .unwrap()
+        .response;
+    let request = received(&server, true).await;
+    assert_eq!(
+        json_body(&request),
+        json!({"type": "A", "name": "www", "data": "192.0.2.2"})
+    );
+    assert_eq!(response.record().data, "192.0.2.2");
+}
+
+#[tokio::test]
+async fn delete_dns_record() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "DELETE",
+        "/address/foobar/dns/41923511",
+        "delete_dns_record",
+    )
+    .await;
+
+    let response = auth_client(&server)
+        .delete_dns_record("foobar", 41923511)
+        .await
+        .unwrap()
+        .response;
+    received(&server, true).await;
+    assert!(response.message.contains("deleted"));
+}
+
+// Statuslog
+
+#[tokio::test]
+async fn get_status() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/statuses/63fd3e8d2a6f8",
+        "get_status",
+    )
+    .await;
+
+    let status = auth_client(&server)
+        .get_status("foobar", "63fd3e8d2a6f8")
+        .await
+        .unwrap()
+        .response
+        .status;
+    received(&server, true).await;
+    assert_eq!(status.content, "Writing Rust.");
+}
+
+#[tokio::test]
+async fn post_status() {
+    let server = mock_server().await;
+    serve(&server, "POST", "/address/foobar/statuses", "post_status").await;
+
+    let mut status = Status::new("🦀".to_string(), "Writing Rust.".to_string(), None);
+    status.address = "foobar".to_string();
+    let response = auth_client(&server)
+        .post_status(&status)
+        .await
+        .unwrap()
+        .response;
+    let request = received(&server, true).await;
+    assert_eq!(
+        json_body(&request),
+        json!({"emoji": "🦀", "content": "Writing Rust.", "external_url": null})
+    );
+    assert_eq!(response.id, "63fd3e8d2a6f8");
+}
+
+#[tokio::test]
+async fn update_status() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "PATCH",
+        "/address/foobar/statuses",
+        "update_status",
+    )
+    .await;
+
+    let mut status = Status::new("🦀".to_string(), "Still writing Rust.".to_string(), None);
+    status.address = "foobar".to_string();
+    status.id = "63fd3e8d2a6f8".to_string();
+    let response = auth_client(&server)
+        .update_status(&status)
+        .await
+        .unwrap()
+        .response;
+    let request = received(&server, true).await;
+    assert_eq!(
+        json_body(&request),
+        json!({
+            "id": "63fd3e8d2a6f8",
+            "emoji": "🦀",
+            "content": "Still writing Rust.",
+            "external_url": null,
+        })
+    );
+    assert_eq!(response.id, "63fd3e8d2a6f8");
+}
+
+#[tokio::test]
+async fn delete_status() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "DELETE",
+        "/address/foobar/statuses/63fd3e8d2a6f8",
+        "delete_status",
+    )
+    .await;
+
+    auth_client(&server)
+        .delete_status("foobar", "63fd3e8d2a6f8")
+        .await
+        .unwrap();
+    received(&server, true).await;
+}
+
+#[tokio::test]
+async fn update_statuslog_bio() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "POST",
+        "/address/foobar/statuses/bio",
+        "update_statuslog_bio",
+    )
+    .await;
+
+    let bio = StatuslogBio::new("I write \"Rust\".".to_string());
+    let response = auth_client(&server)
+        .update_statuslog_bio(bio, "foobar")
+        .await
+        .unwrap()
+        .response;
+    let request = received(&server, true).await;
+    assert_eq!(json_body(&request), json!({"content": "I write \"Rust\"."}));
+    assert_eq!(response.bio, "I write \"Rust\".");
+}
+
+#[tokio::test]
+async fn get_statuslog_bio() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/statuses/bio",
+        "get_statuslog_bio",
+    )
+    .await;
+
+    let bio = public_client(&server)
+        .get_statuslog_bio("foobar")
+        .await
+        .unwrap()
+        .response;
+    received(&server, false).await;
+    assert_eq!(bio.bio, "I write Rust.");
+}
+
+#[tokio::test]
+async fn get_all_statuses() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/statuses",
+        "get_all_statuses",
+    )
+    .await;
+
+    let statuses = public_client(&server)
+        .get_all_statuses("foobar")
+        .await
+        .unwrap()
+        .response
+        .statuses;
+    received(&server, false).await;
+    assert_eq!(statuses.len(), 2);
+}
+
+#[tokio::test]
+async fn get_latest_status() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/statuses/latest",
+        "get_latest_status",
+    )
+    .await;
+
+    let status = public_client(&server)
+        .get_latest_status("foobar")
+        .await
+        .unwrap()
+        .response
+        .status;
+    received(&server, false).await;
+    assert_eq!(status.id, "63fd3e8d2a6f8");
+}
+
+#[tokio::test]
+async fn get_statuslog() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/statuslog", "get_statuslog").await;
+
+    let statuses = public_client(&server)
+        .get_statuslog()
+        .await
+        .unwrap()
+        .response
+        .statuses;
+    received(&server, false).await;
+    assert_eq!(statuses[1].address, "barbaz");
+}
+
+#[tokio::test]
+async fn get_statuslog_latest() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/statuslog/latest", "get_statuslog_latest").await;
+
+    let statuses = public_client(&server)
+        .get_statuslog_latest()
+        .await
+        .unwrap()
+        .response
+        .statuses;
+    received(&server, false).await;
+    assert_eq!(statuses.len(), 1);
+}
+
+// Email
+
+#[tokio::test]
+async fn get_forwarding_addresses() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/email",
+        "get_forwarding_addresses",
+    )
+    .await;
+
+    let forwarding = auth_client(&server)
+        .get_forwarding_addresses("foobar")
+        .await
+        .unwrap()
+        .response;
+    received(&server, true).await;
+    assert_eq!(forwarding.destination_array.len(), 2);
+    assert_eq!(forwarding.destination_array[0].as_str(), "foo@example.com");
+}
+
+#[tokio::test]
+async fn set_forwarding_addresses() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "POST",
+        "/address/foobar/email",
+        "set_forwarding_addresses",
+    )
+    .await;
+
+    let destinations = vec![
+        EmailAddress::from_str("foo@example.com").unwrap(),
+        EmailAddress::from_str("bar@example.com").unwrap(),
+    ];
+    auth_client(&server)
+        .set_forwarding_addresses("foobar", &destinations)
+        .await
+        .unwrap();
+    let request = received(&server, true).await;
+    assert_eq!(
+        json_body(&request),
+        json!({"destination": "foo@example.com, bar@example.com"})
+    );
+}
+
+// Now page
+
+#[tokio::test]
+async fn get_now_page() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/address/foobar/now", "get_now_page").await;
+
+    auth_client(&server).get_now_page("foobar").await.unwrap();
+    received(&server, true).await;
+}
+
+#[tokio::test]
+async fn update_now_page() {
+    let server = mock_server().await;
+    serve(&server, "POST", "/address/foobar/now", "update_now_page").await;
+
+    let now = NowPageUpdate::new("# Now".to_string(), false);
+    auth_client(&server)
+        .update_now_page("foobar", &now)
+        .await
+        .unwrap();
+    let request = received(&server, true).await;
+    assert_eq!(
+        json_body(&request),
+        json!({"content": "# Now", "listed": 0})
+    );
+}
+
+#[tokio::test]
+async fn get_now_garden() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/now/garden", "get_now_garden").await;
+
+    public_client(&server).get_now_garden().await.unwrap();
+    received(&server, false).await;
+}
+
+// Pastebin
+
+#[tokio::test]
+async fn get_pastebin() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/address/foobar/pastebin", "get_pastebin").await;
+
+    let pastes = auth_client(&server)
+        .get_pastebin("foobar")
+        .await
+        .unwrap()
+        .response
+        .pastebin;
+    received(&server, true).await;
+    assert_eq!(pastes.len(), 2);
+    assert!(pastes[0].listed);
+    assert_eq!(pastes[0].modified_on, Some(1677523264));
+    assert!(!pastes[1].listed);
+}
+
+#[tokio::test]
+async fn get_listed_pastes() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/pastebin",
+        "get_listed_pastes",
+    )
+    .await;
+
+    let pastes = public_client(&server)
+        .get_listed_pastes("foobar")
+        .await
+        .unwrap()
+        .response
+        .pastebin;
+    received(&server, false).await;
+    assert!(pastes.iter().all(|paste| paste.listed));
+}
+
+#[tokio::test]
+async fn get_paste() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/pastebin/hello.rs",
+        "get_paste",
+    )
+    .await;
+
+    let paste = public_client(&server)
+        .get_paste("foobar", "hello.rs")
+        .await
+        .unwrap()
+        .response
+        .paste;
+    received(&server, false).await;
+    assert_eq!(paste.title, "hello.rs");
+}
+
+#[tokio::test]
+async fn upload_paste() {
+    let server = mock_server().await;
+    serve(&server, "POST", "/address/foobar/pastebin", "upload_paste").await;
+
+    let paste = Paste::new("hello.rs".to_string(), "fn main() {}".to_string()).listed(true);
+    let response = auth_client(&server)
+        .upload_paste("foobar", &paste)
+        .await
+        .unwrap()
+        .response;
+    let request = received(&server, true).await;
+    assert_eq!(
+        json_body(&request),
+        json!({"title": "hello.rs", "content": "fn main() {}", "listed": 1})
+    );
+    assert_eq!(response.title, "hello.rs");
+}
+
+#[tokio::test]
+async fn delete_paste() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "DELETE",
+        "/address/foobar/pastebin/hello.rs",
+        "delete_paste",
+    )
+    .await;
+
+    auth_client(&server)
+        .delete_paste("foobar", "hello.rs")
+        .await
+        .unwrap();
+    received(&server, true).await;
+}
+
+// Weblog
+
+#[tokio::test]
+async fn create_weblog_entry() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "POST",
+        "/address/foobar/weblog/entry/hello-world",
+        "create_weblog_entry",
+    )
+    .await;
+
+    let source = "# Hello, world\n\nMy first post.";
+    let entry = auth_client(&server)
+        .create_weblog_entry(source, "hello-world", "foobar")
+        .await
+        .unwrap()
+        .response
+        .entry;
+    let request = received(&server, true).await;
+    assert_eq!(text_body(&request), source);
+    assert_eq!(entry.metadata.slug, "hello-world");
+}
+
+#[tokio::test]
+async fn get_weblog_entry() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/weblog/entry/hello-world",
+        "get_weblog_entry",
+    )
+    .await;
+
+    let entry = auth_client(&server)
+        .get_weblog_entry("hello-world", "foobar")
+        .await
+        .unwrap()
+        .response
+        .entry;
+    received(&server, true).await;
+    assert_eq!(entry.title, "Hello, world");
+}
+
+#[tokio::test]
+async fn delete_weblog_entry() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "DELETE",
+        "/address/foobar/weblog/delete/hello-world",
+        "delete_weblog_entry",
+    )
+    .await;
+
+    auth_client(&server)
+        .delete_weblog_entry("hello-world", "foobar")
+        .await
+        .unwrap();
+    received(&server, true).await;
+}
+
+#[tokio::test]
+async fn get_latest_weblog_post() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/weblog/post/latest",
+        "get_latest_weblog_post",
+    )
+    .await;
+
+    let entry = public_client(&server)
+        .get_latest_weblog_post("foobar")
+        .await
+        .unwrap()
+        .response
+        .entry;
+    received(&server, false).await;
+    assert_eq!(entry.location, "/2023/02/hello-world");
+}
+
+#[tokio::test]
+async fn get_weblog_configuration() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/weblog/configuration",
+        "get_weblog_configuration",
+    )
+    .await;
+
+    let configuration = auth_client(&server)
+        .get_weblog_configuration("foobar")
+        .await
+        .unwrap()
+        .response
+        .configuration;
+    received(&server, true).await;
+    assert_eq!(configuration.object.weblog_title, "foobar's weblog");
+}
+
+#[tokio::test]
+async fn update_weblog_configuration() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "POST",
+        "/address/foobar/weblog/configuration",
+        "update_weblog_configuration",
+    )
+    .await;
+
+    let configuration = "weblog_title: foobar's weblog";
+    auth_client(&server)
+        .update_weblog_configuration(configuration, "foobar")
+        .await
+        .unwrap();
+    let request = received(&server, true).await;
+    assert_eq!(text_body(&request), configuration);
+}
+
+#[tokio::test]
+async fn get_weblog_template() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/weblog/template",
+        "get_weblog_template",
+    )
+    .await;
+
+    let template = auth_client(&server)
+        .get_weblog_template("foobar")
+        .await
+        .unwrap()
+        .response
+        .template;
+    received(&server, true).await;
+    assert!(template.contains("{body}"));
+}
+
+#[tokio::test]
+async fn update_weblog_template() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "POST",
+        "/address/foobar/weblog/template",
+        "update_weblog_template",
+    )
+    .await;
+
+    let template = "<!DOCTYPE html>\n<html>{body}</html>\n";
+    auth_client(&server)
+        .update_weblog_template(template, "foobar")
+        .await
+        .unwrap();
+    let request = received(&server, true).await;
+    assert_eq!(text_body(&request), template);
+}
+
+// PURLs
+
+#[tokio::test]
+async fn create_purl() {
+    let server = mock_server().await;
+    serve(&server, "POST", "/address/foobar/purl", "create_purl").await;
+
+    let purl = auth_client(&server)
+        .create_purl("foobar", "rust", "https://www.rust-lang.org", true)
+        .await
+        .unwrap()
+        .response
+        .purl;
+    let request = received(&server, true).await;
+    assert_eq!(
+        json_body(&request),
+        json!({"name": "rust", "url": "https://www.rust-lang.org/", "listed": 1})
+    );
+    assert_eq!(purl.url.as_str(), "https://www.rust-lang.org/");
+    assert!(purl.listed);
+}
+
+#[tokio::test]
+async fn create_purl_rejects_invalid_urls() {
+    let server = mock_server().await;
+    let client = auth_client(&server);
+
+    for url in ["www.rust-lang.org", "javascript:alert(1)", ""] {
+        let error = client
+            .create_purl("foobar", "rust", url, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, OmglolError::Validation(_)));
+    }
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn get_purl() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/address/foobar/purl/rust", "get_purl").await;
+
+    let purl = auth_client(&server)
+        .get_purl("foobar", "rust")
+        .await
+        .unwrap()
+        .response
+        .purl;
+    received(&server, true).await;
+    assert_eq!(purl.counter, Some(42));
+    assert_eq!(purl.url.host_str(), Some("www.rust-lang.org"));
+    assert!(purl.listed);
+}
+
+#[tokio::test]
+async fn get_all_purls() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/address/foobar/purls", "get_all_purls").await;
+
+    let purls = auth_client(&server)
+        .get_all_purls("foobar")
+        .await
+        .unwrap()
+        .response
+        .purls;
+    received(&server, true).await;
+    assert_eq!(purls.len(), 2);
+    assert!(purls[0].listed);
+    assert!(!purls[1].listed);
+}
+
+#[tokio::test]
+async fn delete_purl() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "DELETE",
+        "/address/foobar/purl/rust",
+        "delete_purl",
+    )
+    .await;
+
+    auth_client(&server)
+        .delete_purl("foobar", "rust")
+        .await
+        .unwrap();
+    received(&server, true).await;
+}
+
+// Account and address
+
+#[tokio::test]
+async fn get_account_info() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/account/foo@example.com/info",
+        "get_account_info",
+    )
+    .await;
+
+    let email = EmailAddress::from_str("foo@example.com").unwrap();
+    let account = auth_client(&server)
+        .get_account_info(&email)
+        .await
+        .unwrap()
+        .response;
+    received(&server, true).await;
+    assert_eq!(account.settings.owner, "foo@example.com");
+}
+
+#[tokio::test]
+async fn get_private_address_info() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/info",
+        "get_private_address_info",
+    )
+    .await;
+
+    let address = auth_client(&server)
+        .get_private_address_info("foobar")
+        .await
+        .unwrap()
+        .response;
+    received(&server, true).await;
+    assert_eq!(address.owner.as_deref(), Some("foo@example.com"));
+}
+
+#[tokio::test]
+async fn get_public_address_info() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/info",
+        "get_public_address_info",
+    )
+    .await;
+
+    let address = public_client(&server)
+        .get_public_address_info("foobar")
+        .await
+        .unwrap()
+        .response;
+    received(&server, false).await;
+    assert_eq!(address.owner, None);
+    assert_eq!(address.expiration.will_expire, Some(true));
+}
+
+#[tokio::test]
+async fn get_address_expiration() {
+    let server = mock_server().await;
+    serve(
+        &server,
+        "GET",
+        "/address/foobar/expiration",
+        "get_address_expiration",
+    )
+    .await;
+
+    let expiration = auth_client(&server)
+        .get_address_expiration("foobar")
+        .await
+        .unwrap()
+        .response;
+    received(&server, true).await;
+    assert!(!expiration.expired);
+}
+
+// Web page
+
+#[tokio::test]
+async fn get_web_page() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/address/foobar/web", "get_web_page").await;
+
+    let web = auth_client(&server)
+        .get_web_page("foobar")
+        .await
+        .unwrap()
+        .response;
+    received(&server, true).await;
+    assert_eq!(web.content, "# Hello");
+}
+
+#[tokio::test]
+async fn update_web_page() {
+    let server = mock_server().await;
+    serve(&server, "POST", "/address/foobar/web", "update_web_page").await;
+
+    let web = Web::new("# Hello again".to_string());
+    auth_client(&server)
+        .update_web_page(&web, "foobar")
+        .await
+        .unwrap();
+    let request = received(&server, true).await;
+    assert_eq!(json_body(&request), json!({"content": "# Hello again"}));
+}
+
+// Service
+
+#[tokio::test]
+async fn get_profile_themes() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/theme/list", "get_profile_themes").await;
+
+    let themes = public_client(&server)
+        .get_profile_themes()
+        .await
+        .unwrap()
+        .response
+        .themes;
+    received(&server, false).await;
+    assert!(themes.contains_key("default"));
+}
+
+#[tokio::test]
+async fn service_status() {
+    let server = mock_server().await;
+    serve(&server, "GET", "/service/info", "service_status").await;
+
+    let service = public_client(&server)
+        .service_status()
+        .await
+        .unwrap()
+        .response;
+    received(&server, false).await;
+    assert_eq!(service.members, 1000);
+}
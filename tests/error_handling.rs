@@ -0,0 +1,151 @@
+mod common;
+
+use common::{envelope, mock_server, ok};
+use omglol::{OmglolClient, OmglolError};
+
+use reqwest::Method;
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+async fn service_info_replies(server: &MockServer, response: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(path("/service/info"))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+async fn expect_decode_error(response: ResponseTemplate) -> OmglolError {
+    let server = mock_server().await;
+    service_info_replies(&server, response).await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+
+    let error = client.service_status().await.unwrap_err();
+    match &error {
+        OmglolError::Decode { method, path, .. } => {
+            assert_eq!(method, Method::GET);
+            assert_eq!(path, "service/info");
+        }
+        other => panic!("expected a decode error, got {:?}", other),
+    }
+    error
+}
+
+#[tokio::test]
+async fn non_json_body() {
+    let error =
+        expect_decode_error(ResponseTemplate::new(200).set_body_string("<html>oops</html>")).await;
+    assert_eq!(error.body_snippet(), Some("<html>oops</html>"));
+}
+
+#[tokio::test]
+async fn empty_body() {
+    let error = expect_decode_error(ResponseTemplate::new(200)).await;
+    assert_eq!(error.body_snippet(), Some(""));
+}
+
+#[tokio::test]
+async fn truncated_json() {
+    expect_decode_error(ResponseTemplate::new(200).set_body_string(
+        r#"{"request": {"status_code": 200, "success": true}, "response": {"mess"#,
+    ))
+    .await;
+}
+
+#[tokio::test]
+async fn missing_response_key() {
+    expect_decode_error(
+        ResponseTemplate::new(200)
+            .set_body_json(json!({"request": {"status_code": 200, "success": true}})),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn missing_request_key() {
+    expect_decode_error(ResponseTemplate::new(200).set_body_json(json!({
+        "response": {"message": "", "members": 1, "addresses": 1, "profiles": 1},
+    })))
+    .await;
+}
+
+#[tokio::test]
+async fn schema_drift() {
+    expect_decode_error(ok(json!({
+        "message": "omg.lol is home to many members.",
+        "members": "lots",
+        "addresses": 1500,
+        "profiles": 900,
+    })))
+    .await;
+}
+
+#[tokio::test]
+async fn long_body_is_cut_in_snippet() {
+    let body = "🦀".repeat(1000);
+    let error = expect_decode_error(ResponseTemplate::new(200).set_body_string(body.clone())).await;
+
+    assert_eq!(error.body_snippet().unwrap().chars().count(), 200);
+    match error {
+        OmglolError::Decode { body: raw, .. } => assert_eq!(raw, body),
+        other => panic!("expected a decode error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn error_status_without_envelope() {
+    let server = mock_server().await;
+    service_info_replies(
+        &server,
+        ResponseTemplate::new(502).set_body_string("Bad Gateway"),
+    )
+    .await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+
+    match client.service_status().await {
+        Err(OmglolError::Status {
+            status_code: 502,
+            message: None,
+        }) => {}
+        other => panic!("expected a status error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn error_status_with_envelope() {
+    let server = mock_server().await;
+    service_info_replies(
+        &server,
+        ResponseTemplate::new(404).set_body_json(envelope(
+            404,
+            json!({"message": "That endpoint does not exist."}),
+        )),
+    )
+    .await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+
+    let error = client.service_status().await.unwrap_err();
+    assert_eq!(error.status_code(), Some(404));
+    assert!(error.to_string().contains("That endpoint does not exist."));
+}
+
+#[tokio::test]
+async fn missing_api_key() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth(String::new());
+
+    match client.get_dns_records("foobar").await {
+        Err(OmglolError::MissingApiKey { method, path }) => {
+            assert_eq!(method, Method::GET);
+            assert_eq!(path, "address/foobar/dns");
+        }
+        other => panic!("expected a missing API key error, got {:?}", other),
+    }
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
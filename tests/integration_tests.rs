@@ -1,36 +1,72 @@
+mod common;
+
+use common::{envelope, mock_server, ok};
 use omglol::{
     client::{Auth, NoAuth},
-    structures::DNStype,
-    OmglolClient,
+    structures::{DNSrecordBuilder, DNStype},
+    OmglolClient, OmglolError,
 };
 
 use dotenv::dotenv;
+use serde_json::json;
 use std::env;
+use wiremock::{
+    matchers::{body_json, header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
 
-fn init_noauth_client() -> (OmglolClient<NoAuth>, String) {
+fn init_noauth_client(server: &MockServer) -> (OmglolClient<NoAuth>, String) {
     dotenv().ok();
     let address = env::var("OMGLOL_ADDRESS").unwrap_or("foobar".to_string());
-    let client_noauth = OmglolClient::new();
+    let client_noauth = OmglolClient::builder().base_url(server.uri()).build();
     println!("Using account {} for testing", &address);
 
-    return (client_noauth, address);
+    (client_noauth, address)
+}
+
+fn api_key() -> String {
+    dotenv().ok();
+    env::var("OMGLOL_API_KEY").unwrap_or("test-api-key".to_string())
 }
 
-fn init_auth_client() -> (OmglolClient<Auth>, String) {
+fn init_auth_client(server: &MockServer) -> (OmglolClient<Auth>, String) {
     dotenv().ok();
     let address = env::var("OMGLOL_ADDRESS").unwrap_or("foobar".to_string());
-    let api_key = env::var("OMGLOL_API_KEY").unwrap_or("".to_string());
-    let client = OmglolClient::new().auth(api_key);
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth(api_key());
     println!("Using account {} for testing", &address);
 
-    return (client, address);
+    (client, address)
 }
 
 #[tokio::test]
 async fn get_all_statuses() {
-    let (client, address) = init_noauth_client();
+    let server = mock_server().await;
+    let (client, address) = init_noauth_client(&server);
+    Mock::given(method("GET"))
+        .and(path(format!("/address/{}/statuses", &address)))
+        .respond_with(ok(json!({
+            "message": "You have 1 status.",
+            "statuses": [{
+                "id": "63f4e2bd1a1f9",
+                "address": &address,
+                "created": "1676993213",
+                "relative_time": "1 day ago",
+                "emoji": "🦀",
+                "content": "Writing Rust.",
+                "external_url": null,
+            }],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
     let response = client.get_all_statuses(&address).await.unwrap().response;
     println!("{:#?}", response);
+    assert_eq!(response.statuses.len(), 1);
+    assert_eq!(response.statuses[0].emoji, "🦀");
 }
 
 #[test]
@@ -40,28 +76,294 @@ fn dns_display() {
 
 #[tokio::test]
 async fn get_service_status() {
-    let (client, _) = init_noauth_client();
+    let server = mock_server().await;
+    let (client, _) = init_noauth_client(&server);
+    Mock::given(method("GET"))
+        .and(path("/service/info"))
+        .respond_with(ok(json!({
+            "message": "omg.lol is home to 1,000 members.",
+            "members": 1000,
+            "addresses": 1500,
+            "profiles": 900,
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
     let service_status = client.service_status().await.unwrap().response;
     println!("{:#?}", service_status);
+    assert_eq!(service_status.members, 1000);
 }
 
 #[tokio::test]
 async fn get_profile_themes() {
-    let (client, _) = init_noauth_client();
+    let server = mock_server().await;
+    let (client, _) = init_noauth_client(&server);
+    Mock::given(method("GET"))
+        .and(path("/theme/list"))
+        .respond_with(ok(json!({
+            "message": "Here are the available themes.",
+            "themes": {
+                "default": {
+                    "id": "default",
+                    "name": "Default",
+                    "created": "1660967179",
+                    "updated": "1660967179",
+                    "author": "omg.lol",
+                    "author_url": "https://omg.lol",
+                    "version": "1",
+                    "license": "MIT",
+                    "description": "The default theme.",
+                    "preview_css": "",
+                    "sample_profile": "",
+                },
+            },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
     let info = client.get_profile_themes().await.unwrap().response;
     println!("{:#?}", info);
+    assert!(info.themes.contains_key("default"));
 }
 
 #[tokio::test]
 async fn get_dns_records() {
-    let (client, address) = init_auth_client();
+    let server = mock_server().await;
+    let (client, address) = init_auth_client(&server);
+    Mock::given(method("GET"))
+        .and(path(format!("/address/{}/dns", &address)))
+        .and(header(
+            "Authorization",
+            format!("Bearer {}", api_key()).as_str(),
+        ))
+        .respond_with(ok(json!({
+            "message": "Your DNS records were retrieved.",
+            "dns": [{
+                "id": 41923511,
+                "type": "A",
+                "name": &address,
+                "data": "192.0.2.1",
+                "priority": null,
+                "ttl": 3600,
+                "created_at": "2023-01-01T00:00:00Z",
+                "updated_at": "2023-01-01T00:00:00Z",
+            }],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
     let response = client.get_dns_records(&address).await.unwrap().response;
     println!("{:#?}", response);
+    assert_eq!(response.dns[0].data, "192.0.2.1");
 }
 
 #[tokio::test]
 async fn get_web_page() {
-    let (client, address) = init_auth_client();
+    let server = mock_server().await;
+    let (client, address) = init_auth_client(&server);
+    Mock::given(method("GET"))
+        .and(path(format!("/address/{}/web", &address)))
+        .respond_with(ok(json!({
+            "message": "Your web content has been retrieved.",
+            "content": "# Hello",
+            "type": "profile",
+            "theme": "default",
+            "css": "",
+            "head": "",
+            "verified": 0,
+            "pfp": null,
+            "metadata": "{}",
+            "branding": "default",
+            "modified": "2023-01-01 00:00:00",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
     let response = client.get_web_page(&address).await.unwrap().response;
     println!("{:#?}", response);
+    assert_eq!(response.content, "# Hello");
+}
+
+#[tokio::test]
+async fn status_error_carries_api_message() {
+    let server = mock_server().await;
+    let (client, address) = init_auth_client(&server);
+    Mock::given(method("GET"))
+        .and(path(format!("/address/{}/web", &address)))
+        .respond_with(ResponseTemplate::new(401).set_body_json(envelope(
+            401,
+            json!({ "message": "Your API key is invalid." }),
+        )))
+        .mount(&server)
+        .await;
+
+    match client.get_web_page(&address).await {
+        Err(OmglolError::Status {
+            status_code,
+            message,
+        }) => {
+            assert_eq!(status_code, 401);
+            assert_eq!(message.as_deref(), Some("Your API key is invalid."));
+        }
+        other => panic!("expected a status error, got {:?}", other),
+    }
+}
+
+fn dns_record_response(id: i32, record_type: &str, name: &str, content: &str) -> serde_json::Value {
+    json!({
+        "message": "Your DNS record was saved.",
+        "data_sent": {
+            "type": record_type,
+            "name": name,
+            "content": content,
+        },
+        "response_received": {
+            "data": {
+                "id": id,
+                "name": name,
+                "content": content,
+                "ttl": 3600,
+                "priority": null,
+                "type": record_type,
+                "created_at": "2023-01-01T00:00:00Z",
+                "updated_at": "2023-01-01T00:00:00Z",
+            },
+        },
+    })
+}
+
+#[tokio::test]
+async fn create_dns_record() {
+    let server = mock_server().await;
+    let (client, address) = init_auth_client(&server);
+    Mock::given(method("POST"))
+        .and(path(format!("/address/{}/dns", &address)))
+        .and(body_json(json!({
+            "type": "TXT",
+            "name": "_test",
+            "data": "hello world",
+            "ttl": 300,
+        })))
+        .respond_with(ok(dns_record_response(
+            41923511,
+            "TXT",
+            "_test.foobar",
+            "hello world",
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let record =
+        DNSrecordBuilder::new(DNStype::TXT, "_test".to_string(), "hello world".to_string())
+            .ttl(300);
+    let response = client
+        .create_dns_record(&address, &record)
+        .await
+        .unwrap()
+        .response;
+    assert_eq!(response.record().id, 41923511);
+    assert_eq!(response.record().data, "hello world");
+}
+
+#[tokio::test]
+async fn update_dns_record() {
+    let server = mock_server().await;
+    let (client, address) = init_auth_client(&server);
+    Mock::given(method("PATCH"))
+        .and(path(format!("/address/{}/dns/41923511", &address)))
+        .and(body_json(json!({
+            "type": "A",
+            "name": "www",
+            "data": "192.0.2.2",
+        })))
+        .respond_with(ok(dns_record_response(
+            41923511,
+            "A",
+            "www.foobar",
+            "192.0.2.2",
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let record = DNSrecordBuilder::new(DNStype::A, "www".to_string(), "192.0.2.2".to_string());
+    let response = client
+        .update_dns_record(&address, 41923511, &record)
+        .await
+        .unwrap()
+        .response;
+    assert_eq!(response.record().data, "192.0.2.2");
+}
+
+#[tokio::test]
+async fn invalid_dns_record_is_not_sent() {
+    let server = mock_server().await;
+    let (client, address) = init_auth_client(&server);
+
+    let record = DNSrecordBuilder::new(DNStype::A, "www".to_string(), "not-an-ip".to_string());
+    assert!(matches!(
+        client.create_dns_record(&address, &record).await,
+        Err(OmglolError::Validation(_))
+    ));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[test]
+fn dns_record_validation() {
+    let mx = DNSrecordBuilder::new(DNStype::MX, "".to_string(), "mx.example.com".to_string());
+    assert!(mx.validate().is_err());
+    assert!(mx.priority(10).validate().is_ok());
+
+    let txt = DNSrecordBuilder::new(DNStype::TXT, "".to_string(), "v=spf1 -all".to_string());
+    assert!(txt.clone().priority(10).validate().is_err());
+    assert!(txt.clone().ttl(0).validate().is_err());
+    assert!(txt.validate().is_ok());
+
+    let srv = DNSrecordBuilder::new(
+        DNStype::SRV,
+        "_sip._tcp".to_string(),
+        "5 5060 sip.example.com".to_string(),
+    );
+    assert!(srv.priority(10).validate().is_ok());
+}
+
+#[test]
+fn dns_type_round_trip() {
+    for name in [
+        "A", "AAAA", "CAA", "CNAME", "MX", "NS", "SRV", "TXT", "HTTPS",
+    ] {
+        let record_type: DNStype = serde_json::from_value(json!(name)).unwrap();
+        assert_eq!(record_type.to_string(), name);
+        assert_eq!(serde_json::to_value(&record_type).unwrap(), json!(name));
+    }
+
+    assert_eq!("aaaa".parse::<DNStype>().unwrap(), DNStype::AAAA);
+    assert_eq!(
+        "HTTPS".parse::<DNStype>().unwrap(),
+        DNStype::Other("HTTPS".to_string())
+    );
+    assert!("".parse::<DNStype>().is_err());
+}
+
+#[test]
+fn dns_records_with_unknown_types_decode() {
+    let records: omglol::structures::DNSrecords = serde_json::from_value(json!({
+        "message": "Your DNS records were retrieved.",
+        "dns": [
+            {"id": 1, "type": "AAAA", "name": "foobar", "data": "2001:db8::1", "priority": null, "ttl": 3600},
+            {"id": 2, "type": "HTTPS", "name": "foobar", "data": "1 . alpn=h2", "priority": null, "ttl": 3600},
+        ],
+    }))
+    .unwrap();
+
+    assert_eq!(records.dns[0].record_type, DNStype::AAAA);
+    assert_eq!(
+        records.dns[1].record_type,
+        DNStype::Other("HTTPS".to_string())
+    );
 }
@@ -0,0 +1,74 @@
+mod common;
+
+use common::{mock_server, replay};
+use omglol::{structures::NowPageUpdate, OmglolClient};
+
+use serde_json::json;
+use wiremock::{
+    matchers::{body_json, header, method, path},
+    Mock,
+};
+
+#[tokio::test]
+async fn get_now_page() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/now"))
+        .and(header("Authorization", "Bearer test-api-key"))
+        .respond_with(replay("get_now_page"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let now = client.get_now_page("foobar").await.unwrap().response.now;
+    assert!(now.content.starts_with("{profile-picture}"));
+    assert_eq!(now.updated, 1677523264);
+    assert!(now.listed);
+    assert!(now.nudge);
+}
+
+#[tokio::test]
+async fn update_now_page() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+    Mock::given(method("POST"))
+        .and(path("/address/foobar/now"))
+        .and(header("Authorization", "Bearer test-api-key"))
+        .and(body_json(json!({"content": "# Now", "listed": 0})))
+        .respond_with(replay("update_now_page"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let now = NowPageUpdate::new("# Now".to_string(), false);
+    let response = client.update_now_page("foobar", &now).await.unwrap();
+    assert_eq!(
+        response.response.message,
+        "Your /now page has been updated."
+    );
+}
+
+#[tokio::test]
+async fn get_now_garden() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/now/garden"))
+        .respond_with(replay("get_now_garden"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let garden = client.get_now_garden().await.unwrap().response.garden;
+    assert_eq!(garden.len(), 2);
+    assert_eq!(garden[0].url, "https://foobar.omg.lol/now");
+    assert_eq!(garden[0].updated.unix_epoch_time, "1677523264");
+    assert_eq!(garden[1].updated.unix_epoch_time, "1677350464");
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use common::{envelope, mock_server};
+use omglol::{oauth::OAuthClient, retry::RetryPolicy, OmglolClient, OmglolError};
+
+use serde_json::json;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use wiremock::{
+    matchers::{header, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn oauth_client(server: &MockServer) -> OAuthClient {
+    OAuthClient::new(
+        &OmglolClient::builder().base_url(server.uri()).build(),
+        "client-id",
+        "client-secret",
+        "https://example.com/callback?from=omg.lol",
+    )
+}
+
+#[tokio::test]
+async fn authorize_url() {
+    let server = mock_server().await;
+    let url = oauth_client(&server).authorize_url("s3cr3t state").unwrap();
+
+    assert_eq!(
+        url.as_str(),
+        "https://home.omg.lol/oauth/authorize?client_id=client-id\
+         &redirect_uri=https%3A%2F%2Fexample.com%2Fcallback%3Ffrom%3Domg.lol\
+         &scope=everything&response_type=code&state=s3cr3t+state"
+    );
+
+    let custom = oauth_client(&server)
+        .authorize_endpoint("not a url")
+        .authorize_url("state");
+    assert!(matches!(custom, Err(OmglolError::Validation(_))));
+}
+
+#[tokio::test]
+async fn exchange_code_for_client() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/oauth/"))
+        .and(query_param("client_id", "client-id"))
+        .and(query_param("client_secret", "client-secret"))
+        .and(query_param(
+            "redirect_uri",
+            "https://example.com/callback?from=omg.lol",
+        ))
+        .and(query_param("code", "the-code"))
+        .and(query_param("scope", "everything"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "member-token",
+            "token_type": "Bearer",
+            "scope": "everything",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/dns"))
+        .and(header("Authorization", "Bearer member-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(envelope(
+            200,
+            json!({"message": "Your DNS records were retrieved.", "dns": []}),
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = oauth_client(&server)
+        .authenticate("the-code")
+        .await
+        .unwrap();
+    let records = client.get_dns_records("foobar").await.unwrap();
+    assert!(records.response.dns.is_empty());
+}
+
+#[tokio::test]
+async fn exchange_code_accepts_envelope() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/oauth/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(envelope(
+            200,
+            json!({
+                "access_token": "member-token",
+                "token_type": "Bearer",
+                "scope": "everything",
+            }),
+        )))
+        .mount(&server)
+        .await;
+
+    let token = oauth_client(&server)
+        .exchange_code("the-code")
+        .await
+        .unwrap();
+    assert_eq!(token.access_token, "member-token");
+}
+
+#[tokio::test]
+async fn rejected_code() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/oauth/"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(envelope(
+            401,
+            json!({"message": "The authorization code is invalid."}),
+        )))
+        .mount(&server)
+        .await;
+
+    let error = oauth_client(&server)
+        .exchange_code("bad-code")
+        .await
+        .unwrap_err();
+    assert_eq!(error.status_code(), Some(401));
+
+    let empty = oauth_client(&server).exchange_code(" ").await;
+    assert!(matches!(empty, Err(OmglolError::Validation(_))));
+}
+
+#[tokio::test]
+async fn transport_errors_hide_the_secret() {
+    // Nothing listens on port 1, so the request cannot be sent.
+    let client = OmglolClient::builder()
+        .base_url("http://127.0.0.1:1/")
+        .build();
+    let error = OAuthClient::new(&client, "client-id", "client-secret", "https://example.com")
+        .exchange_code("the-code")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, OmglolError::Transport(_)));
+    let message = error.to_string();
+    assert!(!message.contains("client-secret"), "{}", message);
+    assert!(!message.contains("the-code"), "{}", message);
+}
+
+#[tokio::test]
+async fn code_exchange_is_never_retried() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/oauth/"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    let hook_paths = Arc::new(Mutex::new(Vec::new()));
+    let seen = hook_paths.clone();
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .retry_policy(
+            RetryPolicy::new()
+                .max_attempts(3)
+                .initial_backoff(Duration::from_millis(5))
+                .retry_when(move |_, path| {
+                    seen.lock().unwrap().push(path.to_string());
+                    true
+                }),
+        )
+        .build();
+
+    let error = OAuthClient::new(&client, "client-id", "client-secret", "https://example.com")
+        .exchange_code("the-code")
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.status_code(), Some(503));
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    assert!(hook_paths
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|path| !path.contains("client-secret")));
+}
@@ -0,0 +1,325 @@
+mod common;
+
+use common::{mock_server, ok};
+use omglol::{
+    pastebin::sync::{self, Direction, SyncAction, SyncOptions, SyncPlan},
+    structures::Paste,
+    OmglolClient,
+};
+
+use serde_json::json;
+use std::{fs, path::PathBuf};
+use wiremock::{
+    matchers::{body_json, method, path},
+    Mock, ResponseTemplate,
+};
+
+fn paste(title: &str, content: &str, modified_on: i64, listed: bool) -> Paste {
+    Paste {
+        modified_on: Some(modified_on),
+        ..Paste::new(title.to_string(), content.to_string()).listed(listed)
+    }
+}
+
+fn local() -> Vec<Paste> {
+    vec![
+        paste("same.rs", "fn main() {}", 200, false),
+        paste("edited.md", "# New", 300, false),
+        paste("clash.txt", "local", 100, false),
+        paste("new.sh", "echo hi", 300, false),
+    ]
+}
+
+fn remote() -> Vec<Paste> {
+    vec![
+        paste("same.rs", "fn main() {}", 100, true),
+        paste("edited.md", "# Old", 100, true),
+        paste("clash.txt", "remote", 200, false),
+        paste("stale.txt", "remove me", 100, false),
+    ]
+}
+
+fn directory(name: &str) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("omglol-pastebin-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    directory
+}
+
+#[test]
+fn push_plan_diffs_files_against_pastes() {
+    let options = SyncOptions::new().prune(true).listed(true);
+    let plan = SyncPlan::new("foobar", "snippets", &local(), &remote(), &options);
+
+    assert!(matches!(
+        &plan.actions[0],
+        SyncAction::Conflict { current, .. } if current.content == "remote"
+    ));
+    assert!(matches!(
+        &plan.actions[1],
+        SyncAction::Update { desired, .. } if desired.content == "# New" && desired.listed
+    ));
+    assert!(matches!(
+        &plan.actions[2],
+        SyncAction::Create(desired) if desired.title == "new.sh" && desired.listed
+    ));
+    assert!(matches!(&plan.actions[3], SyncAction::Unchanged(_)));
+    assert!(matches!(&plan.actions[4], SyncAction::Delete(paste) if paste.title == "stale.txt"));
+    assert_eq!(plan.actions.len(), 5);
+    assert_eq!(plan.conflicts().count(), 1);
+}
+
+#[test]
+fn plan_options() {
+    let plan = SyncPlan::new(
+        "foobar",
+        "snippets",
+        &local(),
+        &remote(),
+        &SyncOptions::new().overwrite(true),
+    );
+    assert!(matches!(&plan.actions[0], SyncAction::Update { .. }));
+    assert!(plan
+        .actions
+        .iter()
+        .all(|action| action.title() != "stale.txt"));
+
+    let plan = SyncPlan::new(
+        "foobar",
+        "snippets",
+        &remote(),
+        &remote(),
+        &SyncOptions::new().prune(true),
+    );
+    assert!(!plan.has_changes());
+}
+
+#[test]
+fn dry_run_output() {
+    let options = SyncOptions::new().prune(true);
+    let push = SyncPlan::new("foobar", "snippets", &local(), &remote(), &options);
+    assert_eq!(
+        push.to_string(),
+        "Pastebin plan for foobar (push from snippets):
+  ! clash.txt (newer on paste.lol)
+  ~ edited.md
+  + new.sh
+  = same.rs
+  - stale.txt
+"
+    );
+
+    let pull = SyncPlan::new(
+        "foobar",
+        "snippets",
+        &local(),
+        &remote(),
+        &options.direction(Direction::Pull),
+    );
+    assert_eq!(
+        pull.to_string(),
+        "Pastebin plan for foobar (pull into snippets):
+  ~ clash.txt
+  ! edited.md (newer locally)
+  - new.sh
+  = same.rs
+  + stale.txt
+"
+    );
+}
+
+#[tokio::test]
+async fn push_uploads_and_prunes() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+    let directory = directory("push");
+    fs::create_dir_all(directory.join("nested")).unwrap();
+    fs::write(directory.join("hello.rs"), "fn main() {}").unwrap();
+    fs::write(directory.join("notes.md"), "# Notes").unwrap();
+    fs::write(directory.join(".hidden"), "secret").unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/pastebin"))
+        .respond_with(ok(json!({
+            "message": "Your pastes were retrieved.",
+            "pastebin": [
+                {"title": "hello.rs", "content": "fn main() {}", "modified_on": 1, "listed": 1},
+                {"title": "notes.md", "content": "# Old", "modified_on": 1, "listed": 1},
+                {"title": "stale.txt", "content": "remove me", "modified_on": 1, "listed": null},
+            ],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/address/foobar/pastebin"))
+        .and(body_json(
+            json!({"title": "notes.md", "content": "# Notes", "listed": 1}),
+        ))
+        .respond_with(ok(json!({
+            "message": "Your paste has been saved.",
+            "title": "notes.md",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/address/foobar/pastebin/stale.txt"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let options = SyncOptions::new().prune(true);
+    let plan = sync::plan(&client, "foobar", &directory, &options)
+        .await
+        .unwrap();
+    let report = plan.apply(&client).await;
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(plan.actions.len(), 3);
+    assert_eq!(report.results.len(), 2);
+    let failures: Vec<_> = report.failures().collect();
+    assert_eq!(failures.len(), 1);
+    assert!(matches!(&failures[0].action, SyncAction::Delete(paste) if paste.title == "stale.txt"));
+}
+
+#[tokio::test]
+async fn pull_writes_files() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+    let directory = directory("pull");
+
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/pastebin"))
+        .respond_with(ok(json!({
+            "message": "Your pastes were retrieved.",
+            "pastebin": [
+                {"title": "hello.rs", "content": "fn main() {}", "modified_on": 1, "listed": 1},
+                {"title": "sub/../../escape", "content": "nope", "modified_on": 1, "listed": 1},
+            ],
+        })))
+        .mount(&server)
+        .await;
+
+    let options = SyncOptions::new().direction(Direction::Pull);
+    let plan = sync::plan(&client, "foobar", &directory, &options)
+        .await
+        .unwrap();
+    let report = plan.apply(&client).await;
+    let written = fs::read_to_string(directory.join("hello.rs")).unwrap();
+    let pastes = sync::read_directory(&directory).unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(written, "fn main() {}");
+    assert_eq!(pastes.len(), 1);
+    let failures: Vec<_> = report.failures().collect();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].action.title(), "sub/../../escape");
+}
+
+#[tokio::test]
+async fn hidden_pastes_survive_pull_then_prune() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+    let directory = directory("hidden");
+
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/pastebin"))
+        .respond_with(ok(json!({
+            "message": "Your pastes were retrieved.",
+            "pastebin": [
+                {"title": "hello.rs", "content": "fn main() {}", "modified_on": 1, "listed": 1},
+                {"title": ".foo", "content": "hidden", "modified_on": 1, "listed": null},
+            ],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let pull = SyncOptions::new().direction(Direction::Pull);
+    let pulled = sync::plan(&client, "foobar", &directory, &pull)
+        .await
+        .unwrap()
+        .apply(&client)
+        .await;
+    let push = sync::plan(
+        &client,
+        "foobar",
+        &directory,
+        &SyncOptions::new().prune(true),
+    )
+    .await
+    .unwrap();
+    let pushed = push.apply(&client).await;
+    let hidden_written = directory.join(".foo").exists();
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert!(pulled.is_success());
+    assert!(!hidden_written);
+    assert!(!push.has_changes());
+    assert!(pushed.results.is_empty());
+}
+
+#[tokio::test]
+async fn titles_are_encoded_in_urls() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+    let directory = directory("encoded");
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("notes"), "keep me").unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/pastebin"))
+        .respond_with(ok(json!({
+            "message": "Your pastes were retrieved.",
+            "pastebin": [
+                {"title": "notes", "content": "keep me", "modified_on": 1, "listed": 1},
+                {"title": "notes?v=2#x", "content": "stale", "modified_on": 1, "listed": 1},
+            ],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/address/foobar/pastebin/notes%3Fv=2%23x"))
+        .respond_with(ok(json!({"message": "OK, that paste has been deleted."})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/address/foobar/pastebin/notes"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let plan = sync::plan(
+        &client,
+        "foobar",
+        &directory,
+        &SyncOptions::new().prune(true),
+    )
+    .await
+    .unwrap();
+    let report = plan.apply(&client).await;
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(report.results.len(), 1);
+    assert!(report.is_success());
+}
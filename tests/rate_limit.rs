@@ -0,0 +1,111 @@
+mod common;
+
+use common::{mock_server, ok};
+use omglol::{
+    rate_limit::{route_group, Budget, RateLimiter},
+    OmglolClient,
+};
+
+use serde_json::json;
+use std::time::{Duration, Instant};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer,
+};
+
+async fn mount_endpoints(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/service/info"))
+        .respond_with(ok(json!({
+            "message": "omg.lol is home to 1,000 members.",
+            "members": 1000,
+            "addresses": 1500,
+            "profiles": 900,
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/dns"))
+        .respond_with(ok(json!({"message": "Your DNS records.", "dns": []})))
+        .mount(server)
+        .await;
+}
+
+#[test]
+fn route_groups() {
+    assert_eq!(route_group("address/foobar/dns/123"), "dns");
+    assert_eq!(route_group("address/foobar/statuses/latest"), "statuses");
+    assert_eq!(route_group("address/foobar"), "address");
+    assert_eq!(route_group("account/foo@example.com/info"), "account");
+    assert_eq!(route_group("statuslog/latest"), "statuslog");
+    assert_eq!(route_group("oauth/?code=abc"), "oauth");
+}
+
+#[tokio::test]
+async fn waits_instead_of_failing() {
+    let server = mock_server().await;
+    mount_endpoints(&server).await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .rate_limiter(RateLimiter::new(Budget::new(2, Duration::from_millis(200))))
+        .build();
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        client.service_status().await.unwrap();
+    }
+
+    // Two requests burst through, the other three wait 100ms each.
+    assert!(start.elapsed() >= Duration::from_millis(280));
+}
+
+#[tokio::test]
+async fn clones_share_the_bucket() {
+    let server = mock_server().await;
+    mount_endpoints(&server).await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .rate_limiter(RateLimiter::new(Budget::new(1, Duration::from_millis(100))))
+        .build();
+    let authenticated = client.auth("test-api-key".to_string());
+
+    let start = Instant::now();
+    let clones: Vec<_> = (0..4).map(|_| client.clone()).collect();
+    futures::future::join_all(clones.iter().map(|client| client.service_status())).await;
+    authenticated.get_dns_records("foobar").await.unwrap();
+
+    assert!(start.elapsed() >= Duration::from_millis(380));
+}
+
+#[tokio::test]
+async fn route_groups_have_their_own_budget() {
+    let server = mock_server().await;
+    mount_endpoints(&server).await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .rate_limiter(
+            RateLimiter::new(Budget::per_second(1000))
+                .group("dns", Budget::new(1, Duration::from_millis(150))),
+        )
+        .build()
+        .auth("test-api-key".to_string());
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        client.get_dns_records("foobar").await.unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(280));
+
+    let unauthenticated = OmglolClient::builder()
+        .base_url(server.uri())
+        .rate_limiter(
+            RateLimiter::new(Budget::per_second(1000))
+                .group("dns", Budget::new(1, Duration::from_secs(60))),
+        )
+        .build();
+    let start = Instant::now();
+    for _ in 0..3 {
+        unauthenticated.service_status().await.unwrap();
+    }
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::{envelope, mock_server, ok};
+use omglol::{retry::RetryPolicy, structures::NowPageUpdate, OmglolClient};
+
+use serde_json::json;
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn accepts_created() {
+    let server = mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/address/foobar/now"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(envelope(
+            201,
+            json!({"message": "Your /now page has been saved."}),
+        )))
+        .mount(&server)
+        .await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+
+    let now = NowPageUpdate::new("# Now".to_string(), true);
+    let res = client.update_now_page("foobar", &now).await.unwrap();
+    assert_eq!(res.request.status_code, 201);
+    assert_eq!(res.metadata().status_code, 201);
+    assert_eq!(res.response.message, "Your /now page has been saved.");
+}
+
+#[tokio::test]
+async fn accepts_no_content() {
+    let server = mock_server().await;
+    Mock::given(method("DELETE"))
+        .and(path("/address/foobar/statuses/63f4a2d7b2c6c"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+
+    let res = client
+        .delete_status("foobar", "63f4a2d7b2c6c")
+        .await
+        .unwrap();
+    assert_eq!(res.request.status_code, 204);
+    assert!(res.request.success);
+    assert_eq!(res.response.message, "");
+    assert_eq!(res.metadata().status_code, 204);
+}
+
+#[tokio::test]
+async fn exposes_headers_and_attempts() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/service/info"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/service/info"))
+        .respond_with(
+            ok(json!({
+                "message": "omg.lol is home to 1,000 members.",
+                "members": 1000,
+                "addresses": 1500,
+                "profiles": 900,
+            }))
+            .insert_header("X-Request-Id", "abc123"),
+        )
+        .mount(&server)
+        .await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .retry_policy(
+            RetryPolicy::new()
+                .initial_backoff(Duration::from_millis(20))
+                .jitter(false),
+        )
+        .build();
+
+    let res = client.service_status().await.unwrap();
+    let metadata = res.metadata();
+    assert_eq!(metadata.status_code, 200);
+    assert_eq!(metadata.headers["x-request-id"], "abc123");
+    assert_eq!(metadata.attempts, 2);
+    assert!(metadata.duration >= Duration::from_millis(20));
+}
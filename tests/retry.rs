@@ -0,0 +1,170 @@
+mod common;
+
+use common::{mock_server, ok};
+use omglol::{retry::RetryPolicy, structures::Status, OmglolClient};
+
+use reqwest::Method;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy::new()
+        .max_attempts(3)
+        .initial_backoff(Duration::from_millis(5))
+        .jitter(false)
+}
+
+fn service_info() -> ResponseTemplate {
+    ok(json!({
+        "message": "omg.lol is home to 1,000 members.",
+        "members": 1000,
+        "addresses": 1500,
+        "profiles": 900,
+    }))
+}
+
+async fn script(server: &MockServer, http_method: &str, endpoint: &str, failures: &[u16]) {
+    for status_code in failures {
+        Mock::given(method(http_method))
+            .and(path(endpoint))
+            .respond_with(ResponseTemplate::new(*status_code))
+            .up_to_n_times(1)
+            .mount(server)
+            .await;
+    }
+}
+
+async fn request_count(server: &MockServer) -> usize {
+    server.received_requests().await.unwrap().len()
+}
+
+#[tokio::test]
+async fn retries_server_errors_until_success() {
+    let server = mock_server().await;
+    script(&server, "GET", "/service/info", &[503, 500]).await;
+    Mock::given(method("GET"))
+        .and(path("/service/info"))
+        .respond_with(service_info())
+        .mount(&server)
+        .await;
+
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build();
+
+    assert_eq!(
+        client.service_status().await.unwrap().response.members,
+        1000
+    );
+    assert_eq!(request_count(&server).await, 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let server = mock_server().await;
+    script(&server, "GET", "/service/info", &[502, 502, 502, 502]).await;
+
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build();
+
+    let error = client.service_status().await.unwrap_err();
+    assert_eq!(error.status_code(), Some(502));
+    assert_eq!(request_count(&server).await, 3);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let server = mock_server().await;
+    script(&server, "GET", "/service/info", &[404]).await;
+
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build();
+
+    assert!(client.service_status().await.is_err());
+    assert_eq!(request_count(&server).await, 1);
+}
+
+#[tokio::test]
+async fn no_retries_by_default() {
+    let server = mock_server().await;
+    script(&server, "GET", "/service/info", &[503]).await;
+
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+
+    assert!(client.service_status().await.is_err());
+    assert_eq!(request_count(&server).await, 1);
+}
+
+#[tokio::test]
+async fn honours_retry_after() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/service/info"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/service/info"))
+        .respond_with(service_info())
+        .mount(&server)
+        .await;
+
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build();
+
+    let start = Instant::now();
+    client.service_status().await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(900));
+    assert_eq!(request_count(&server).await, 2);
+}
+
+fn status() -> Status {
+    let mut status = Status::new("🦀".to_string(), "Retrying".to_string(), None);
+    status.address = "foobar".to_string();
+    status
+}
+
+#[tokio::test]
+async fn post_is_not_retried_by_default() {
+    let server = mock_server().await;
+    script(&server, "POST", "/address/foobar/statuses", &[503, 503]).await;
+
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .retry_policy(fast_policy())
+        .build()
+        .auth("test-api-key".to_string());
+
+    assert!(client.post_status(&status()).await.is_err());
+    assert_eq!(request_count(&server).await, 1);
+}
+
+#[tokio::test]
+async fn post_can_be_opted_in() {
+    let server = mock_server().await;
+    script(&server, "POST", "/address/foobar/statuses", &[503, 503]).await;
+
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .retry_policy(
+            fast_policy()
+                .retry_when(|method, path| *method == Method::POST && path.ends_with("/statuses")),
+        )
+        .build()
+        .auth("test-api-key".to_string());
+
+    assert!(client.post_status(&status()).await.is_err());
+    assert_eq!(request_count(&server).await, 3);
+}
@@ -0,0 +1,301 @@
+mod common;
+
+use common::{mock_server, ok};
+use omglol::{
+    statuslog::{CursorStore, FileCursor, MemoryCursor, StatusPoller},
+    structures::Status,
+    OmglolClient, OmglolError,
+};
+
+use futures::{Stream, StreamExt};
+use serde_json::{json, Value};
+use std::time::Duration;
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, ResponseTemplate,
+};
+
+fn status(id: &str, address: &str, content: &str) -> Value {
+    json!({
+        "id": id,
+        "address": address,
+        "created": "1676993213",
+        "relative_time": "1 day ago",
+        "emoji": "🦀",
+        "content": content,
+        "external_url": null,
+    })
+}
+
+#[tokio::test]
+async fn delete_status() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("test-api-key".to_string());
+    Mock::given(method("DELETE"))
+        .and(path("/address/foobar/statuses/63f4e2bd1a1f9"))
+        .and(header("Authorization", "Bearer test-api-key"))
+        .respond_with(ok(json!({"message": "OK, this status has been deleted."})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client
+        .delete_status("foobar", "63f4e2bd1a1f9")
+        .await
+        .unwrap()
+        .response;
+    assert_eq!(response.message, "OK, this status has been deleted.");
+}
+
+#[tokio::test]
+async fn get_latest_status() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/statuses/latest"))
+        .respond_with(ok(json!({
+            "message": "Here is the latest status.",
+            "status": status("63f4e2bd1a1f9", "foobar", "Latest"),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.get_latest_status("foobar").await.unwrap().response;
+    assert_eq!(response.status.content, "Latest");
+}
+
+#[tokio::test]
+async fn get_statuslog() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/statuslog"))
+        .respond_with(ok(json!({
+            "message": "Here is the statuslog.",
+            "statuses": [
+                status("2", "foobar", "Second"),
+                status("1", "barbaz", "First"),
+            ],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.get_statuslog().await.unwrap().response;
+    assert_eq!(response.statuses.len(), 2);
+    assert_eq!(response.statuses[1].address, "barbaz");
+}
+
+#[tokio::test]
+async fn get_statuslog_latest() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/statuslog/latest"))
+        .respond_with(ok(json!({
+            "message": "Here are the latest statuses.",
+            "statuses": [status("2", "foobar", "Second")],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.get_statuslog_latest().await.unwrap().response;
+    assert_eq!(response.statuses[0].id, "2");
+}
+
+fn statuses(ids: &[&str]) -> ResponseTemplate {
+    ok(json!({
+        "message": "Here are the statuses.",
+        "statuses": ids
+            .iter()
+            .map(|id| status(id, "foobar", &format!("Status {}", id)))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+async fn next_ids<S>(stream: &mut S, count: usize) -> Vec<String>
+where
+    S: Stream<Item = Result<Status, OmglolError>> + Unpin,
+{
+    let mut ids = Vec::new();
+    for _ in 0..count {
+        ids.push(stream.next().await.unwrap().unwrap().id);
+    }
+    ids
+}
+
+#[tokio::test]
+async fn poller_yields_new_statuses_once() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/statuses"))
+        .respond_with(statuses(&["2", "1"]))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/statuses"))
+        .respond_with(statuses(&["4", "3", "2", "1"]))
+        .mount(&server)
+        .await;
+
+    let cursor = MemoryCursor::new();
+    let mut stream = Box::pin(
+        StatusPoller::address(&client, "foobar")
+            .interval(Duration::from_millis(10))
+            .cursor_store(cursor.clone())
+            .into_stream(),
+    );
+
+    assert_eq!(next_ids(&mut stream, 4).await, ["1", "2", "3", "4"]);
+    assert_eq!(cursor.get().as_deref(), Some("4"));
+
+    let nothing_new = tokio::time::timeout(Duration::from_millis(100), stream.next()).await;
+    assert!(nothing_new.is_err());
+}
+
+#[tokio::test]
+async fn poller_resumes_from_cursor() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/statuslog"))
+        .respond_with(statuses(&["4", "3", "2", "1"]))
+        .mount(&server)
+        .await;
+
+    let directory = std::env::temp_dir().join(format!("omglol-cursor-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let cursor_path = directory.join("cursor");
+    std::fs::write(&cursor_path, "2\n").unwrap();
+
+    let mut stream = Box::pin(
+        StatusPoller::statuslog(&client)
+            .interval(Duration::from_millis(10))
+            .cursor_store(FileCursor::new(&cursor_path))
+            .into_stream(),
+    );
+
+    assert_eq!(next_ids(&mut stream, 2).await, ["3", "4"]);
+    assert_eq!(std::fs::read_to_string(&cursor_path).unwrap(), "4");
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn poller_skips_existing_statuses() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/statuses"))
+        .respond_with(statuses(&["2", "1"]))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/statuses"))
+        .respond_with(statuses(&["3", "2", "1"]))
+        .mount(&server)
+        .await;
+
+    let mut stream = Box::pin(
+        StatusPoller::address(&client, "foobar")
+            .interval(Duration::from_millis(10))
+            .skip_existing(true)
+            .into_stream(),
+    );
+
+    assert_eq!(next_ids(&mut stream, 1).await, ["3"]);
+}
+
+#[tokio::test]
+async fn poller_reports_errors_and_recovers() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/statuses"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/statuses"))
+        .respond_with(statuses(&["1"]))
+        .mount(&server)
+        .await;
+
+    let mut stream = Box::pin(
+        StatusPoller::address(&client, "foobar")
+            .interval(Duration::from_millis(10))
+            .max_backoff(Duration::from_millis(30))
+            .into_stream(),
+    );
+
+    for _ in 0..2 {
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(error.status_code(), Some(503));
+    }
+    assert_eq!(next_ids(&mut stream, 1).await, ["1"]);
+}
+
+#[tokio::test]
+async fn poller_ignores_older_statuses_moving_into_view() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/statuslog"))
+        .respond_with(statuses(&["5", "4", "3", "2"]))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    // "4" was deleted, letting "1" into the window.
+    Mock::given(method("GET"))
+        .and(path("/statuslog"))
+        .respond_with(statuses(&["6", "5", "3", "2", "1"]))
+        .mount(&server)
+        .await;
+
+    let mut stream = Box::pin(
+        StatusPoller::statuslog(&client)
+            .interval(Duration::from_millis(10))
+            .into_stream(),
+    );
+
+    assert_eq!(next_ids(&mut stream, 5).await, ["2", "3", "4", "5", "6"]);
+    let nothing_new = tokio::time::timeout(Duration::from_millis(100), stream.next()).await;
+    assert!(nothing_new.is_err());
+}
+
+#[tokio::test]
+async fn poller_skips_existing_when_the_cursor_is_gone() {
+    let server = mock_server().await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/statuses"))
+        .respond_with(statuses(&["2", "1"]))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/statuses"))
+        .respond_with(statuses(&["3", "2", "1"]))
+        .mount(&server)
+        .await;
+
+    let cursor = MemoryCursor::new();
+    cursor.save("deleted").unwrap();
+    let mut stream = Box::pin(
+        StatusPoller::address(&client, "foobar")
+            .interval(Duration::from_millis(10))
+            .cursor_store(cursor.clone())
+            .into_stream(),
+    );
+
+    assert_eq!(next_ids(&mut stream, 1).await, ["3"]);
+    assert_eq!(cursor.get().as_deref(), Some("3"));
+}
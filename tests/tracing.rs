@@ -0,0 +1,106 @@
+mod common;
+
+use common::{mock_server, replay};
+use omglol::OmglolClient;
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+use tracing_subscriber::fmt::{format::FmtSpan, MakeWriter};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+/// Collects everything the subscriber writes.
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Captured {
+    fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Captured {
+    type Writer = Captured;
+
+    fn make_writer(&'a self) -> Captured {
+        self.clone()
+    }
+}
+
+fn subscriber(captured: &Captured) -> impl tracing::Subscriber {
+    tracing_subscriber::fmt()
+        .with_writer(captured.clone())
+        .with_max_level(tracing::Level::TRACE)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_ansi(false)
+        .finish()
+}
+
+#[tokio::test]
+async fn span_has_route_and_status() {
+    let captured = Captured::default();
+    let _guard = tracing::subscriber::set_default(subscriber(&captured));
+
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/address/foobar/now"))
+        .respond_with(replay("get_now_page"))
+        .mount(&server)
+        .await;
+    let client = OmglolClient::builder()
+        .base_url(server.uri())
+        .build()
+        .auth("secret-api-key".to_string());
+
+    client.get_now_page("foobar").await.unwrap();
+
+    let output = captured.output();
+    assert!(output.contains("omglol.request"), "{}", output);
+    assert!(output.contains("method=GET"), "{}", output);
+    assert!(
+        output.contains("route=\"address/{address}/now\"")
+            || output.contains("route=address/{address}/now"),
+        "{}",
+        output
+    );
+    assert!(output.contains("address=\"foobar\"") || output.contains("address=foobar"));
+    assert!(output.contains("status=200"), "{}", output);
+    assert!(output.contains("latency_ms="), "{}", output);
+    assert!(!output.contains("secret-api-key"), "{}", output);
+    assert!(!output.contains("Bearer"), "{}", output);
+}
+
+#[tokio::test]
+async fn failures_record_status() {
+    let captured = Captured::default();
+    let _guard = tracing::subscriber::set_default(subscriber(&captured));
+
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/service/info"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    let client = OmglolClient::builder().base_url(server.uri()).build();
+
+    client.service_status().await.unwrap_err();
+
+    let output = captured.output();
+    assert!(output.contains("request failed"), "{}", output);
+    assert!(output.contains("status=404"), "{}", output);
+}
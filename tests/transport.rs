@@ -0,0 +1,166 @@
+mod common;
+
+use common::{envelope, fixture};
+use omglol::{
+    oauth::OAuthClient,
+    retry::RetryPolicy,
+    structures::NowPageUpdate,
+    transport::{HttpRequest, HttpResponse, MemoryTransport, Transport, TransportFuture},
+    OmglolClient, OmglolError,
+};
+
+use reqwest::Method;
+use serde_json::json;
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[tokio::test]
+async fn serves_canned_responses() {
+    let transport = MemoryTransport::new().respond(
+        Method::GET,
+        "address/foobar/now",
+        HttpResponse::new(200, fixture("get_now_page").to_string()),
+    );
+    let client = OmglolClient::builder()
+        .transport(transport.clone())
+        .build()
+        .auth("test-api-key".to_string());
+
+    let now = client.get_now_page("foobar").await.unwrap().response.now;
+    assert!(now.listed);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, Method::GET);
+    assert_eq!(requests[0].path, "address/foobar/now");
+    assert_eq!(requests[0].url, "https://api.omg.lol/address/foobar/now");
+    assert_eq!(requests[0].headers["authorization"], "Bearer test-api-key");
+}
+
+#[tokio::test]
+async fn records_request_bodies() {
+    let transport = MemoryTransport::new().respond(
+        Method::POST,
+        "address/foobar/now",
+        HttpResponse::new(200, envelope(200, json!({"message": "Saved."})).to_string()),
+    );
+    let client = OmglolClient::builder()
+        .transport(transport.clone())
+        .build()
+        .auth("test-api-key".to_string());
+
+    let now = NowPageUpdate::new("# Now".to_string(), true);
+    client.update_now_page("foobar", &now).await.unwrap();
+
+    let body = transport.requests()[0].body.clone().unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        json!({"content": "# Now", "listed": 1})
+    );
+}
+
+#[tokio::test]
+async fn unknown_routes_are_not_found() {
+    let client = OmglolClient::builder()
+        .transport(MemoryTransport::new())
+        .build();
+
+    let error = client.service_status().await.unwrap_err();
+    assert_eq!(error.status_code(), Some(404));
+    assert!(error.to_string().contains("GET service/info"));
+}
+
+/// Refuses the first connection, then serves the service info.
+#[derive(Debug, Default)]
+struct Flaky {
+    attempts: AtomicUsize,
+}
+
+impl Transport for Flaky {
+    fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+        let attempt = self.attempts.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move {
+            if attempt == 0 {
+                return Err(OmglolError::Io(io::ErrorKind::ConnectionRefused.into()));
+            }
+            let body = envelope(
+                200,
+                json!({
+                    "message": "omg.lol is home to 1,000 members.",
+                    "members": 1000,
+                    "addresses": 1500,
+                    "profiles": 900,
+                }),
+            );
+            Ok(HttpResponse::new(200, body.to_string()))
+        })
+    }
+}
+
+#[tokio::test]
+async fn custom_transport_errors_are_retried() {
+    let flaky = Arc::new(Flaky::default());
+    let client = OmglolClient::builder()
+        .transport(flaky.clone())
+        .retry_policy(
+            RetryPolicy::new()
+                .initial_backoff(Duration::from_millis(5))
+                .jitter(false),
+        )
+        .build();
+
+    assert_eq!(
+        client.service_status().await.unwrap().response.members,
+        1000
+    );
+    assert_eq!(flaky.attempts.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn runs_without_tokio() {
+    let transport = MemoryTransport::new().respond(
+        Method::GET,
+        "address/foobar/now",
+        HttpResponse::new(200, fixture("get_now_page").to_string()),
+    );
+    let client = OmglolClient::builder()
+        .transport(transport)
+        .build()
+        .auth("test-api-key".to_string());
+
+    let now = futures::executor::block_on(client.get_now_page("foobar"))
+        .unwrap()
+        .response
+        .now;
+    assert!(now.listed);
+}
+
+#[tokio::test]
+async fn query_is_not_part_of_the_path() {
+    let transport = MemoryTransport::new().respond(
+        Method::GET,
+        "oauth/",
+        HttpResponse::new(
+            200,
+            json!({"access_token": "member-token", "token_type": "Bearer", "scope": "everything"})
+                .to_string(),
+        ),
+    );
+    let client = OmglolClient::builder().transport(transport.clone()).build();
+
+    let token = OAuthClient::new(&client, "client-id", "client-secret", "https://example.com")
+        .exchange_code("the-code")
+        .await
+        .unwrap();
+    assert_eq!(token.access_token, "member-token");
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].path, "oauth/");
+    assert!(requests[0].url.contains("code=the-code"));
+}